@@ -0,0 +1,275 @@
+//! Arrangement of freedesktop menu categories into a browsable hierarchy.
+//!
+//! The hierarchy has two levels: the main categories, and beneath each of them the additional
+//! categories which the menu specification permits alongside it, such as Graphics › Photography.
+
+use std::collections::{BTreeMap, HashSet};
+
+/// Main categories of the freedesktop menu specification, with `Audio` and `Video` treated as
+/// additional categories of `AudioVideo`.
+pub const MAIN_CATEGORIES: &[&str] = &[
+    "AudioVideo",
+    "Development",
+    "Education",
+    "Game",
+    "Graphics",
+    "Network",
+    "Office",
+    "Science",
+    "Settings",
+    "System",
+    "Utility",
+];
+
+/// Main categories that each additional category may appear beneath.
+const ADDITIONAL_CATEGORIES: &[(&str, &[&str])] = &[
+    ("Audio", &["AudioVideo"]),
+    ("Video", &["AudioVideo"]),
+    ("Building", &["Development"]),
+    ("Debugger", &["Development"]),
+    ("IDE", &["Development"]),
+    ("GUIDesigner", &["Development"]),
+    ("Profiling", &["Development"]),
+    ("RevisionControl", &["Development"]),
+    ("Translation", &["Development"]),
+    ("Calendar", &["Office"]),
+    ("ContactManagement", &["Office"]),
+    ("Database", &["Office", "Development", "AudioVideo"]),
+    ("Dictionary", &["Office", "Utility"]),
+    ("Chart", &["Office"]),
+    ("Email", &["Office", "Network"]),
+    ("Finance", &["Office"]),
+    ("FlowChart", &["Office"]),
+    ("PDA", &["Office"]),
+    ("ProjectManagement", &["Office", "Development"]),
+    ("Presentation", &["Office"]),
+    ("Spreadsheet", &["Office"]),
+    ("WordProcessor", &["Office"]),
+    ("2DGraphics", &["Graphics"]),
+    ("VectorGraphics", &["Graphics"]),
+    ("RasterGraphics", &["Graphics"]),
+    ("3DGraphics", &["Graphics"]),
+    ("Scanning", &["Graphics"]),
+    ("OCR", &["Graphics"]),
+    ("Photography", &["Graphics", "Office"]),
+    ("Publishing", &["Graphics", "Office"]),
+    ("Viewer", &["Graphics", "Office"]),
+    ("TextTools", &["Utility"]),
+    ("DesktopSettings", &["Settings"]),
+    ("HardwareSettings", &["Settings"]),
+    ("Printing", &["Settings"]),
+    ("PackageManager", &["Settings"]),
+    ("Dialup", &["Network"]),
+    ("InstantMessaging", &["Network"]),
+    ("Chat", &["Network"]),
+    ("IRCClient", &["Network"]),
+    ("Feed", &["Network"]),
+    ("FileTransfer", &["Network"]),
+    ("HamRadio", &["Network", "AudioVideo"]),
+    ("News", &["Network"]),
+    ("P2P", &["Network"]),
+    ("RemoteAccess", &["Network"]),
+    ("Telephony", &["Network"]),
+    ("TelephonyTools", &["Utility"]),
+    ("VideoConference", &["Network"]),
+    ("WebBrowser", &["Network"]),
+    ("WebDevelopment", &["Network", "Development"]),
+    ("Midi", &["AudioVideo"]),
+    ("Mixer", &["AudioVideo"]),
+    ("Sequencer", &["AudioVideo"]),
+    ("Tuner", &["AudioVideo"]),
+    ("TV", &["AudioVideo"]),
+    ("AudioVideoEditing", &["AudioVideo"]),
+    ("Player", &["AudioVideo"]),
+    ("Recorder", &["AudioVideo"]),
+    ("DiscBurning", &["AudioVideo"]),
+    ("ActionGame", &["Game"]),
+    ("AdventureGame", &["Game"]),
+    ("ArcadeGame", &["Game"]),
+    ("BoardGame", &["Game"]),
+    ("BlocksGame", &["Game"]),
+    ("CardGame", &["Game"]),
+    ("KidsGame", &["Game"]),
+    ("LogicGame", &["Game"]),
+    ("RolePlaying", &["Game"]),
+    ("Shooter", &["Game"]),
+    ("Simulation", &["Game"]),
+    ("SportsGame", &["Game"]),
+    ("StrategyGame", &["Game"]),
+    ("Art", &["Education", "Science"]),
+    ("Construction", &["Education", "Science"]),
+    ("Music", &["AudioVideo", "Education"]),
+    ("Languages", &["Education", "Science"]),
+    ("ArtificialIntelligence", &["Education", "Science"]),
+    ("Astronomy", &["Education", "Science"]),
+    ("Biology", &["Education", "Science"]),
+    ("Chemistry", &["Education", "Science"]),
+    ("ComputerScience", &["Education", "Science"]),
+    ("DataVisualization", &["Education", "Science"]),
+    ("Economy", &["Education", "Science"]),
+    ("Electricity", &["Education", "Science"]),
+    ("Geography", &["Education", "Science"]),
+    ("Geology", &["Education", "Science"]),
+    ("Geoscience", &["Education", "Science"]),
+    ("History", &["Education", "Science"]),
+    ("Humanities", &["Education", "Science"]),
+    ("ImageProcessing", &["Education", "Science"]),
+    ("Literature", &["Education", "Science"]),
+    ("Maps", &["Education", "Science", "Utility"]),
+    ("Math", &["Education", "Science"]),
+    ("NumericalAnalysis", &["Education", "Science"]),
+    ("MedicalSoftware", &["Education", "Science"]),
+    ("Physics", &["Education", "Science"]),
+    ("Robotics", &["Education", "Science"]),
+    ("Spirituality", &["Education", "Science", "Utility"]),
+    ("Sports", &["Education", "Science"]),
+    ("ParallelComputing", &["Education", "Science"]),
+    ("Archiving", &["Utility"]),
+    ("Compression", &["Utility"]),
+    ("Emulator", &["System", "Game"]),
+    ("FileTools", &["Utility", "System"]),
+    ("FileManager", &["System"]),
+    ("TerminalEmulator", &["System"]),
+    ("Filesystem", &["System"]),
+    ("Monitor", &["System", "Network"]),
+    ("Security", &["Settings", "System"]),
+    ("Accessibility", &["Settings", "Utility"]),
+    ("Calculator", &["Utility"]),
+    ("Clock", &["Utility"]),
+    ("TextEditor", &["Utility"]),
+];
+
+pub fn is_main(category: &str) -> bool {
+    MAIN_CATEGORIES.contains(&category)
+}
+
+/// Main categories that an additional category may appear beneath.
+pub fn parents(category: &str) -> &'static [&'static str] {
+    ADDITIONAL_CATEGORIES.iter()
+        .find(|(additional, _)| *additional == category)
+        .map_or(&[], |(_, parents)| parents)
+}
+
+/// A category, along with the number of apps in it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CategoryNode {
+    pub name: String,
+    pub count: usize,
+    pub children: Vec<CategoryNode>,
+}
+
+/// Arranges the categories of each app into the hierarchy, sorted by name at each level.
+///
+/// An app is counted beneath a main category if it lists both, or beneath the first permitted
+/// main category if the app lists none of them.
+pub fn hierarchy<'a>(apps: impl IntoIterator<Item = &'a [String]>) -> Vec<CategoryNode> {
+    let mut mains: BTreeMap<&'static str, (usize, BTreeMap<String, usize>)> = BTreeMap::new();
+
+    for categories in apps {
+        let listed: HashSet<&str> = categories.iter().map(String::as_str).collect();
+        let mut placed: HashSet<&'static str> = HashSet::new();
+
+        for main in MAIN_CATEGORIES.iter().filter(|main| listed.contains(*main)) {
+            mains.entry(main).or_default().0 += 1;
+            placed.insert(main);
+        }
+
+        for category in &listed {
+            let parents = parents(category);
+            let mut matched: Vec<&'static str> = parents.iter()
+                .copied()
+                .filter(|parent| listed.contains(parent))
+                .collect();
+
+            if matched.is_empty() {
+                matched.extend(parents.first().copied());
+            }
+
+            for parent in matched {
+                let entry = mains.entry(parent).or_default();
+                if placed.insert(parent) {
+                    entry.0 += 1;
+                }
+
+                *entry.1.entry((*category).to_owned()).or_insert(0) += 1;
+            }
+        }
+    }
+
+    mains.into_iter()
+        .map(|(name, (count, children))| CategoryNode {
+            name: name.to_owned(),
+            count,
+            children: children.into_iter()
+                .map(|(name, count)| CategoryNode { name, count, children: Vec::new() })
+                .collect(),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn apps(apps: &[&[&str]]) -> Vec<CategoryNode> {
+        let apps: Vec<Vec<String>> = apps.iter()
+            .map(|categories| categories.iter().map(|&c| c.to_owned()).collect())
+            .collect();
+
+        hierarchy(apps.iter().map(Vec::as_slice))
+    }
+
+    fn node(name: &str, count: usize, children: &[(&str, usize)]) -> CategoryNode {
+        CategoryNode {
+            name: name.to_owned(),
+            count,
+            children: children.iter()
+                .map(|&(name, count)| node(name, count, &[]))
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn multiple_parents() {
+        assert_eq!(apps(&[&["Graphics", "Office", "Photography"]]), [
+            node("Graphics", 1, &[("Photography", 1)]),
+            node("Office", 1, &[("Photography", 1)]),
+        ]);
+
+        // Only the main categories that the app lists are used
+        assert_eq!(apps(&[&["Office", "Photography"]]), [
+            node("Office", 1, &[("Photography", 1)]),
+        ]);
+    }
+
+    #[test]
+    fn falls_back_to_first_parent() {
+        assert_eq!(apps(&[&["Photography"], &["Maps"]]), [
+            node("Education", 1, &[("Maps", 1)]),
+            node("Graphics", 1, &[("Photography", 1)]),
+        ]);
+
+        // Main categories that are listed are not given unrelated children
+        assert_eq!(apps(&[&["Utility", "Photography"]]), [
+            node("Graphics", 1, &[("Photography", 1)]),
+            node("Utility", 1, &[]),
+        ]);
+
+        assert!(apps(&[&["GTK", "X-Unknown"]]).is_empty());
+    }
+
+    #[test]
+    fn counts_apps_once_per_main_category() {
+        let hierarchy = apps(&[
+            &["Graphics", "RasterGraphics", "Photography"],
+            &["Photography", "Viewer"],
+            &["Graphics"],
+            &["Game", "Emulator"],
+        ]);
+
+        assert_eq!(hierarchy, [
+            node("Game", 1, &[("Emulator", 1)]),
+            node("Graphics", 3, &[("Photography", 2), ("RasterGraphics", 1), ("Viewer", 1)]),
+        ]);
+    }
+}
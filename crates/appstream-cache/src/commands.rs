@@ -0,0 +1,60 @@
+//! Suggestions of what to install when a command is not found, from the binaries which
+//! components declare that they provide.
+
+/// A package which provides a command, or a command with a similar name.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CommandCandidate {
+    /// The command which the package provides, which differs from the one that was looked up
+    /// if it was only similar.
+    pub command: String,
+    pub origin: String,
+    pub id: String,
+    pub name: String,
+    pub package: String,
+    pub installed: bool,
+    /// Whether the origin is a flatpak remote, rather than an apt repository.
+    pub flatpak: bool,
+}
+
+impl CommandCandidate {
+    /// The command which installs the package, or the app if it is a flatpak.
+    pub fn install_hint(&self) -> String {
+        if self.flatpak {
+            format!("flatpak install {} {}", self.origin, self.target())
+        } else {
+            ["sudo apt install ", &self.package].concat()
+        }
+    }
+
+    /// What is installed: the app ID of a flatpak, or otherwise the package.
+    pub(crate) fn target(&self) -> &str {
+        if self.flatpak {
+            self.id.trim_end_matches(".desktop")
+        } else {
+            &self.package
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn candidate(flatpak: bool) -> CommandCandidate {
+        CommandCandidate {
+            command: "gimp".into(),
+            origin: if flatpak { "flathub" } else { "focal" }.into(),
+            id: "org.gimp.GIMP.desktop".into(),
+            name: "GIMP".into(),
+            package: if flatpak { "" } else { "gimp" }.into(),
+            installed: false,
+            flatpak,
+        }
+    }
+
+    #[test]
+    fn install_hints() {
+        assert_eq!(candidate(false).install_hint(), "sudo apt install gimp");
+        assert_eq!(candidate(true).install_hint(), "flatpak install flathub org.gimp.GIMP");
+    }
+}
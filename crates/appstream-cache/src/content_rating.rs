@@ -0,0 +1,227 @@
+//! Open Age Ratings Service (OARS) content ratings, and the ages that they are suitable for.
+//!
+//! Each attribute of a rating has an intensity, which maps to the minimum age of the Common
+//! Sense Media (CSM) scheme. That age in turn selects a category in other rating systems.
+
+use std::collections::HashMap;
+
+/// Minimum CSM ages for each attribute at the mild, moderate, and intense intensities.
+const ATTRIBUTES: &[(&str, [u8; 3])] = &[
+    ("violence-cartoon", [3, 4, 6]),
+    ("violence-fantasy", [3, 7, 8]),
+    ("violence-realistic", [4, 9, 14]),
+    ("violence-bloodshed", [9, 11, 18]),
+    ("violence-sexual", [18, 18, 18]),
+    ("violence-desecration", [3, 7, 13]),
+    ("violence-slavery", [3, 13, 15]),
+    ("violence-worship", [3, 13, 15]),
+    ("drugs-alcohol", [11, 13, 16]),
+    ("drugs-narcotics", [12, 14, 17]),
+    ("drugs-tobacco", [10, 13, 13]),
+    ("sex-nudity", [12, 14, 14]),
+    ("sex-themes", [13, 14, 15]),
+    ("sex-homosexuality", [13, 14, 15]),
+    ("sex-prostitution", [12, 14, 18]),
+    ("sex-adultery", [8, 10, 18]),
+    ("sex-appearance", [10, 10, 15]),
+    ("language-profanity", [8, 11, 14]),
+    ("language-humor", [3, 8, 14]),
+    ("language-discrimination", [9, 10, 11]),
+    ("money-advertising", [7, 8, 10]),
+    ("money-gambling", [7, 10, 18]),
+    ("money-purchasing", [12, 14, 15]),
+    ("social-chat", [4, 10, 13]),
+    ("social-audio", [15, 15, 15]),
+    ("social-contacts", [12, 12, 12]),
+    ("social-info", [0, 13, 13]),
+    ("social-location", [13, 13, 13]),
+];
+
+/// OARS versions which are understood, from the most preferred.
+const KINDS: &[&str] = &["oars-1.1", "oars-1.0"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Deserialize, Serialize)]
+pub enum Intensity {
+    None,
+    Mild,
+    Moderate,
+    Intense,
+}
+
+impl Intensity {
+    /// Parses an intensity, treating unknown values as `None`.
+    pub fn parse(intensity: &str) -> Self {
+        match intensity.to_ascii_lowercase().as_str() {
+            "mild" => Intensity::Mild,
+            "moderate" => Intensity::Moderate,
+            "intense" => Intensity::Intense,
+            _ => Intensity::None
+        }
+    }
+}
+
+/// Rating systems that a content rating may be presented in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RatingSystem {
+    Csm,
+    Esrb,
+    Pegi,
+}
+
+impl RatingSystem {
+    /// Categories of the rating system, with the minimum age of each.
+    pub fn categories(self) -> &'static [(&'static str, u8)] {
+        match self {
+            RatingSystem::Csm => &[
+                ("0+", 0), ("3+", 3), ("5+", 5), ("7+", 7), ("9+", 9), ("12+", 12),
+                ("13+", 13), ("15+", 15), ("17+", 17), ("18+", 18),
+            ],
+            RatingSystem::Esrb => &[("E", 0), ("E10+", 10), ("T", 13), ("M", 17), ("AO", 18)],
+            RatingSystem::Pegi => &[("3", 0), ("7", 7), ("12", 12), ("16", 16), ("18", 18)],
+        }
+    }
+
+    /// The strictest category whose minimum age does not exceed the CSM age.
+    pub fn category(self, age: u8) -> &'static str {
+        self.categories().iter()
+            .rev()
+            .find(|(_, minimum)| *minimum <= age)
+            .map_or("", |(category, _)| category)
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize, Serialize)]
+pub struct ContentRating {
+    /// The OARS version, such as `oars-1.1`.
+    pub kind: String,
+    /// Intensities of the attributes which the component declared.
+    pub attributes: HashMap<String, Intensity>,
+}
+
+impl ContentRating {
+    /// Selects the most recent OARS version from the ratings of a DEP11 component.
+    pub fn from_dep11(mut ratings: HashMap<String, Option<HashMap<String, String>>>) -> Option<Self> {
+        let kind = KINDS.iter().find(|kind| ratings.contains_key(**kind))?;
+        let attributes = ratings.remove(*kind)?.unwrap_or_default()
+            .into_iter()
+            .map(|(attribute, intensity)| (attribute, Intensity::parse(&intensity)))
+            .collect();
+
+        Some(Self { kind: (*kind).to_owned(), attributes })
+    }
+
+    /// The intensity of an attribute, which is `None` if it was not declared.
+    pub fn intensity(&self, attribute: &str) -> Intensity {
+        self.attributes.get(attribute).copied().unwrap_or(Intensity::None)
+    }
+
+    /// The minimum CSM age that the content is suitable for.
+    pub fn age(&self) -> u8 {
+        self.attributes.iter()
+            .map(|(attribute, intensity)| minimum_age(attribute, *intensity))
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// The category that the content falls into in a rating system.
+    pub fn rating(&self, system: RatingSystem) -> &'static str {
+        system.category(self.age())
+    }
+}
+
+/// Minimum CSM age of an attribute at an intensity. Unknown attributes are ignored.
+pub fn minimum_age(attribute: &str, intensity: Intensity) -> u8 {
+    let ages = match ATTRIBUTES.iter().find(|(known, _)| *known == attribute) {
+        Some((_, ages)) => ages,
+        None => return 0
+    };
+
+    match intensity {
+        Intensity::None => 0,
+        Intensity::Mild => ages[0],
+        Intensity::Moderate => ages[1],
+        Intensity::Intense => ages[2],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rating(attributes: &[(&str, Intensity)]) -> ContentRating {
+        ContentRating {
+            kind: "oars-1.1".into(),
+            attributes: attributes.iter().map(|&(a, i)| (a.to_owned(), i)).collect(),
+        }
+    }
+
+    #[test]
+    fn csm_ages() {
+        assert_eq!(minimum_age("violence-cartoon", Intensity::Mild), 3);
+        assert_eq!(minimum_age("violence-bloodshed", Intensity::Moderate), 11);
+        assert_eq!(minimum_age("sex-nudity", Intensity::Intense), 14);
+        assert_eq!(minimum_age("social-info", Intensity::Mild), 0);
+        assert_eq!(minimum_age("violence-realistic", Intensity::None), 0);
+        assert_eq!(minimum_age("made-up", Intensity::Intense), 0);
+    }
+
+    #[test]
+    fn age_is_the_strictest_attribute() {
+        assert_eq!(ContentRating::default().age(), 0);
+        assert_eq!(rating(&[("violence-cartoon", Intensity::None)]).age(), 0);
+
+        let rating = rating(&[
+            ("violence-cartoon", Intensity::Intense),
+            ("drugs-alcohol", Intensity::Moderate),
+            ("social-chat", Intensity::Mild),
+        ]);
+
+        assert_eq!(rating.age(), 13);
+        assert_eq!(rating.intensity("drugs-alcohol"), Intensity::Moderate);
+        assert_eq!(rating.intensity("sex-themes"), Intensity::None);
+    }
+
+    #[test]
+    fn categories() {
+        assert_eq!(RatingSystem::Csm.category(0), "0+");
+        assert_eq!(RatingSystem::Csm.category(14), "13+");
+        assert_eq!(RatingSystem::Esrb.category(9), "E");
+        assert_eq!(RatingSystem::Esrb.category(10), "E10+");
+        assert_eq!(RatingSystem::Pegi.category(15), "12");
+        assert_eq!(RatingSystem::Pegi.category(18), "18");
+
+        let rating = rating(&[("violence-bloodshed", Intensity::Intense)]);
+        assert_eq!(rating.rating(RatingSystem::Esrb), "AO");
+    }
+
+    #[test]
+    fn dep11_prefers_newest_version() {
+        let mut ratings = HashMap::new();
+        ratings.insert("oars-1.0".to_owned(), Some(
+            std::iter::once(("violence-cartoon".to_owned(), "intense".to_owned())).collect()
+        ));
+        ratings.insert("oars-1.1".to_owned(), Some(
+            std::iter::once(("violence-cartoon".to_owned(), "MILD".to_owned())).collect()
+        ));
+
+        let rating = ContentRating::from_dep11(ratings.clone()).unwrap();
+        assert_eq!(rating.kind, "oars-1.1");
+        assert_eq!(rating.intensity("violence-cartoon"), Intensity::Mild);
+
+        ratings.remove("oars-1.1");
+        assert_eq!(ContentRating::from_dep11(ratings).unwrap().age(), 6);
+    }
+
+    #[test]
+    fn dep11_empty_and_unknown() {
+        let mut ratings = HashMap::new();
+        ratings.insert("oars-1.1".to_owned(), None);
+        let rating = ContentRating::from_dep11(ratings).unwrap();
+        assert!(rating.attributes.is_empty());
+        assert_eq!(rating.age(), 0);
+
+        let mut ratings = HashMap::new();
+        ratings.insert("oars-2.0".to_owned(), Some(HashMap::new()));
+        assert_eq!(ContentRating::from_dep11(ratings), None);
+    }
+}
@@ -26,8 +26,8 @@ pub struct Dep11Package {
     #[serde(rename = "Categories")]
     pub categories: Option<Vec<String>>,
 
-    // #[serde(rename = "Keywords")]
-    // pub keywords: Option<Vec<String>>,
+    #[serde(rename = "Keywords")]
+    pub keywords: Option<HashMap<String, Vec<String>>>,
 
     #[serde(rename = "ProjectLicense")]
     pub license: Option<String>,
@@ -37,6 +37,32 @@ pub struct Dep11Package {
 
     #[serde(rename = "Launchable")]
     pub launchable: Option<Launchable>,
+
+    #[serde(rename = "Screenshots")]
+    pub screenshots: Option<Vec<Screenshot>>,
+
+    #[serde(rename = "Releases")]
+    pub releases: Option<Vec<Dep11Release>>,
+
+    /// Content ratings keyed by OARS version, where an empty rating may be written as null.
+    #[serde(rename = "ContentRating")]
+    pub content_rating: Option<HashMap<String, Option<HashMap<String, String>>>>,
+
+    #[serde(rename = "Provides")]
+    pub provides: Option<Provides>,
+
+    /// IDs of the components which this addon extends.
+    #[serde(rename = "Extends")]
+    pub extends: Option<Vec<String>>,
+
+    #[serde(rename = "DeveloperName")]
+    pub developer_name: Option<HashMap<String, String>>,
+
+    #[serde(rename = "Developer")]
+    pub developer: Option<Dep11Developer>,
+
+    #[serde(rename = "ProjectGroup")]
+    pub project_group: Option<String>,
 }
 
 
@@ -67,4 +93,94 @@ pub struct RemoteIcon {
 pub struct Launchable {
     #[serde(rename = "desktop-id")]
     pub desktop_id: Vec<String>
-}
\ No newline at end of file
+}
+
+
+#[derive(Debug, Default, Deserialize, Serialize)]
+pub struct Screenshot {
+    pub default: Option<bool>,
+    pub caption: Option<HashMap<String, String>>,
+    pub thumbnails: Option<Vec<ScreenshotImage>>,
+    #[serde(rename = "source-image")]
+    pub source_image: Option<ScreenshotImage>,
+    pub videos: Option<Vec<ScreenshotVideo>>,
+}
+
+
+#[derive(Debug, Default, Deserialize, Serialize)]
+pub struct ScreenshotImage {
+    pub url: String,
+    pub width: Option<u32>,
+    pub height: Option<u32>,
+    pub lang: Option<String>,
+}
+
+
+#[derive(Debug, Default, Deserialize, Serialize)]
+pub struct ScreenshotVideo {
+    pub url: String,
+    pub codec: Option<String>,
+    pub container: Option<String>,
+    pub width: Option<u32>,
+    pub height: Option<u32>,
+    pub lang: Option<String>,
+}
+
+
+#[derive(Debug, Default, Deserialize, Serialize)]
+pub struct Dep11Release {
+    pub version: String,
+    #[serde(rename = "type")]
+    pub type_: Option<String>,
+    #[serde(rename = "unix-timestamp")]
+    pub unix_timestamp: Option<i64>,
+    pub urgency: Option<String>,
+    pub description: Option<HashMap<String, String>>,
+}
+
+
+#[derive(Debug, Default, Deserialize, Serialize)]
+pub struct Provides {
+    pub binaries: Option<Vec<String>>,
+    pub libraries: Option<Vec<String>>,
+    #[serde(alias = "mimetypes")]
+    pub mediatypes: Option<Vec<String>>,
+    pub modaliases: Option<Vec<String>>,
+    pub fonts: Option<Vec<ProvidedFont>>,
+    pub python3: Option<Vec<String>>,
+    pub firmware: Option<Vec<ProvidedFirmware>>,
+    pub dbus: Option<Vec<ProvidedDBus>>,
+    pub ids: Option<Vec<String>>,
+}
+
+
+#[derive(Debug, Default, Deserialize, Serialize)]
+pub struct ProvidedFont {
+    pub name: String,
+}
+
+
+#[derive(Debug, Default, Deserialize, Serialize)]
+pub struct ProvidedFirmware {
+    #[serde(rename = "type")]
+    pub type_: Option<String>,
+    /// Name of a firmware file loaded at runtime.
+    pub file: Option<String>,
+    /// GUID of a device whose firmware is flashed.
+    pub guid: Option<String>,
+}
+
+
+#[derive(Debug, Default, Deserialize, Serialize)]
+pub struct ProvidedDBus {
+    #[serde(rename = "type")]
+    pub type_: Option<String>,
+    pub service: String,
+}
+
+
+#[derive(Debug, Default, Deserialize, Serialize)]
+pub struct Dep11Developer {
+    pub id: Option<String>,
+    pub name: Option<HashMap<String, String>>,
+}
@@ -26,7 +26,7 @@ impl Decoder for Dep11Splitter {
 
     fn decode(&mut self, src: &mut BytesMut) -> Result<Option<Self::Item>, Self::Error> {
         loop {
-            match YamlSplitter::default().decode(src) {
+            match YamlSplitter.decode(src) {
                 Ok(Some(bytes)) => {
                     if self.header.is_none() {
                         self.header = Some(
@@ -49,15 +49,15 @@ impl Decoder for Dep11Splitter {
     }
 
     fn decode_eof(&mut self, src: &mut BytesMut) -> Result<Option<Self::Item>, Self::Error> {
-        match YamlSplitter::default().decode_eof(src) {
+        match YamlSplitter.decode_eof(src) {
             Ok(Some(bytes)) => {
                 let package = serde_yaml::from_slice::<Dep11Package>(&bytes)
                     .context("failed to deserialize YAML")
                     .with_context(|| format!("{:?}", std::str::from_utf8(&bytes)))?;
-                return Ok(Some(package))
+                Ok(Some(package))
             }
-            Ok(None) => return Ok(None),
-            Err(why) => return Err(why)
+            Ok(None) => Ok(None),
+            Err(why) => Err(why)
         }
     }
 }
@@ -107,11 +107,11 @@ async fn read_components(path: PathBuf, tx: Sender<PackageEvent>) -> anyhow::Res
                 let _ = tx.send(PackageEvent::MediaUrl { origin: origin.clone(), base_url }).await;
             }
 
-            let _ = tx.send(PackageEvent::Dep11 { origin: origin.clone(), info }).await;
+            let _ = tx.send(PackageEvent::Dep11 { origin: origin.clone(), info: Box::new(info) }).await;
 
             while let Some(event) = stream.next().await {
                 if let Ok(info) = event {
-                    let _ = tx.send(PackageEvent::Dep11 { origin: origin.clone(), info }).await;
+                    let _ = tx.send(PackageEvent::Dep11 { origin: origin.clone(), info: Box::new(info) }).await;
                 }
             }
         }
@@ -0,0 +1,49 @@
+//! Developers of components, for listing other apps by the same developer.
+
+use crate::locale::{self, Locale, Translations};
+use crate::search::normalize;
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize, Serialize)]
+pub struct Developer {
+    /// A stable identifier of the developer, such as `gnome`, given since AppStream 1.0.
+    pub id: Option<String>,
+    /// Name of the developer, keyed by language.
+    pub names: Translations,
+}
+
+impl Developer {
+    /// Merges the AppStream 1.0 `Developer` with the older `DeveloperName`, which only names
+    /// the developer. Returns `None` if neither identifies a developer.
+    pub fn new(id: Option<String>, names: Option<Translations>) -> Option<Self> {
+        let names = names.map(locale::normalize_keys).unwrap_or_default();
+        let id = id.filter(|id| !id.is_empty());
+
+        if id.is_none() && names.is_empty() {
+            None
+        } else {
+            Some(Self { id, names })
+        }
+    }
+
+    pub fn name(&self, locale: &Locale) -> Option<&str> {
+        locale.resolve(&self.names).map(String::as_str)
+    }
+
+    /// Identifies the developer when looking up their other apps, preferring the ID over the
+    /// untranslated name.
+    pub fn key(&self) -> Option<&str> {
+        self.id.as_deref().or_else(|| self.names.get(locale::UNTRANSLATED).map(String::as_str))
+    }
+
+    /// Values that the developer is indexed under: the ID, and every translation of the name.
+    pub(crate) fn index_keys(&self) -> Vec<String> {
+        let mut keys: Vec<String> = self.id.iter()
+            .chain(self.names.values())
+            .map(|key| normalize::fold(key))
+            .collect();
+
+        keys.sort();
+        keys.dedup();
+        keys
+    }
+}
@@ -1,29 +1,35 @@
 use std::env;
 
 use crate::PackageEvent;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use smol::channel::Sender;
 
 const LOCAL: &str = ".local/share/flatpak/appstream/";
+const SYSTEM: &str = "/var/lib/flatpak/appstream/";
 
 pub fn fetch<'a>(executor: &smol::LocalExecutor<'a>, tx: Sender<PackageEvent>) -> anyhow::Result<()> {
     let local_appstream = env::home_dir().unwrap().join(LOCAL);
 
-    for repo_entry in local_appstream.read_dir().unwrap() {
-        if let Ok(repo) = repo_entry {
-            for arch_entry in repo.path().read_dir().unwrap() {
-                if let Ok(arch) = arch_entry {
-                    let appstream = arch.path().join("active/appstream.xml");
+    for repo in local_appstream.read_dir().unwrap().flatten() {
+        for arch in repo.path().read_dir().unwrap().flatten() {
+            let appstream = arch.path().join("active/appstream.xml");
 
-                    executor.spawn(read_xml(appstream, tx.clone())).detach();
-                }
-            }
+            executor.spawn(read_xml(appstream, tx.clone())).detach();
         }
     }
 
     Ok(())
 }
 
-pub async fn read_xml(path: PathBuf, tx: Sender<PackageEvent>) {
-    // TODO:
+/// Checks if an origin is a flatpak remote, from either the system or user installation.
+pub fn is_remote(origin: &str) -> bool {
+    if Path::new(SYSTEM).join(origin).is_dir() {
+        return true;
+    }
+
+    env::var_os("HOME").is_some_and(|home| Path::new(&home).join(LOCAL).join(origin).is_dir())
+}
+
+pub async fn read_xml(_path: PathBuf, _tx: Sender<PackageEvent>) {
+    // TODO: parse the XML catalog, including the releases of each component
 }
\ No newline at end of file
@@ -0,0 +1,159 @@
+//! Discovery of the devices on the system through their modaliases, for recommending the
+//! drivers and firmware which support them.
+
+use std::fs;
+use std::path::Path;
+
+/// Where sysfs is mounted on a running system.
+pub const SYSFS: &str = "/sys";
+
+/// Modaliases of the devices on a system, such as `pci:v000010DEd00001C82sv...`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Hardware {
+    pub modaliases: Vec<String>,
+}
+
+impl Hardware {
+    pub fn scan() -> Self {
+        Self::scan_root(Path::new(SYSFS))
+    }
+
+    /// Reads every `devices/**/modalias` file beneath a sysfs root, which may be a copy of
+    /// sysfs for testing.
+    pub fn scan_root(root: &Path) -> Self {
+        let mut modaliases = Vec::new();
+        read_modaliases(&root.join("devices"), &mut modaliases);
+        modaliases.sort();
+        modaliases.dedup();
+        Self { modaliases }
+    }
+
+    /// Checks if a modalias pattern, as provided by a component, matches any device.
+    pub fn matches(&self, pattern: &str) -> bool {
+        self.modaliases.iter().any(|modalias| crate::provides::glob_match(pattern, modalias))
+    }
+}
+
+fn read_modaliases(path: &Path, modaliases: &mut Vec<String>) {
+    let entries = match path.read_dir() {
+        Ok(entries) => entries,
+        Err(_) => return
+    };
+
+    for entry in entries.filter_map(Result::ok) {
+        // Symlinks are not followed, as sysfs links devices to each other in cycles
+        let file_type = match entry.file_type() {
+            Ok(file_type) => file_type,
+            Err(_) => continue
+        };
+
+        if file_type.is_dir() {
+            read_modaliases(&entry.path(), modaliases);
+        } else if file_type.is_file() && entry.file_name() == "modalias" {
+            if let Ok(modalias) = fs::read_to_string(entry.path()) {
+                let modalias = modalias.trim();
+                if !modalias.is_empty() {
+                    modaliases.push(modalias.to_owned());
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dep11::appstream::{Dep11Package, Provides};
+    use crate::locale::Locale;
+    use crate::Database;
+    use std::path::PathBuf;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir()
+            .join(format!("appstream-cache-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn write_modalias(root: &Path, device: &str, modalias: &str) {
+        let device = root.join("devices").join(device);
+        fs::create_dir_all(&device).unwrap();
+        fs::write(device.join("modalias"), modalias).unwrap();
+    }
+
+    fn scan_fixture(name: &str) -> (PathBuf, Hardware) {
+        let root = temp_dir(name);
+        write_modalias(&root, "pci0000:00/0000:00:02.0", "pci:v00008086d00003E9Bsv00001028\n");
+        write_modalias(&root, "platform/i8042/serio0", "serio:ty01pr00id00ex00\n");
+        write_modalias(&root, "platform/i8042/serio1", "serio:ty01pr00id00ex00\n");
+        write_modalias(&root, "platform/empty", "\n");
+        fs::write(root.join("devices/platform/uevent"), "modalias").unwrap();
+
+        // sysfs links devices to each other in cycles, which must not be followed
+        #[cfg(unix)]
+        std::os::unix::fs::symlink(root.join("devices"), root.join("devices/platform/loop"))
+            .unwrap();
+
+        let hardware = Hardware::scan_root(&root);
+        (root, hardware)
+    }
+
+    #[test]
+    fn scan_and_match() {
+        let (root, hardware) = scan_fixture("scan");
+
+        assert_eq!(hardware.modaliases, [
+            "pci:v00008086d00003E9Bsv00001028",
+            "serio:ty01pr00id00ex00",
+        ]);
+
+        assert!(hardware.matches("pci:v00008086d*"));
+        assert!(hardware.matches("serio:ty01pr00id??ex00"));
+        assert!(!hardware.matches("pci:v000010DEd*"));
+        assert!(!hardware.matches("serio"));
+
+        assert_eq!(Hardware::scan_root(&root.join("missing")), Hardware::default());
+        let _ = fs::remove_dir_all(root);
+    }
+
+    fn component(type_: &str, id: &str, modalias: &str) -> Dep11Package {
+        Dep11Package {
+            type_: type_.into(),
+            id: id.into(),
+            package: id.into(),
+            name: std::iter::once(("C".to_owned(), id.to_owned())).collect(),
+            provides: Some(Provides {
+                modaliases: Some(vec![modalias.into()]),
+                ..Provides::default()
+            }),
+            ..Dep11Package::default()
+        }
+    }
+
+    #[test]
+    fn recommendations() {
+        let (root, hardware) = scan_fixture("devices");
+        let path = temp_dir("recommendations");
+
+        let mut db = Database::new(path.clone(), Locale::new("C"));
+        let locale = db.locale.clone();
+        let origin = db.get_origin("test");
+        origin.add_dep11_package(component("driver", "intel-driver", "pci:v00008086d*"), &locale);
+        origin.add_dep11_package(component("driver", "nvidia-driver", "pci:v000010DEd*"), &locale);
+        origin.add_dep11_package(component("firmware", "serio-firmware", "serio:*"), &locale);
+        origin.add_dep11_package(component("desktop-application", "pci-tool", "pci:*"), &locale);
+
+        let mut recommended: Vec<String> = db.hardware_recommendations(&hardware)
+            .into_iter()
+            .map(|entry| entry.id)
+            .collect();
+
+        recommended.sort();
+        assert_eq!(recommended, ["intel-driver", "serio-firmware"]);
+
+        drop(db);
+        let _ = fs::remove_dir_all(path);
+        let _ = fs::remove_dir_all(root);
+    }
+}
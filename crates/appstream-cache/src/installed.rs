@@ -0,0 +1,76 @@
+//! Discovery of the packages and flatpaks which are installed on the system.
+
+use std::collections::HashSet;
+use std::env;
+use std::fs;
+use std::path::Path;
+
+const DPKG_STATUS: &str = "/var/lib/dpkg/status";
+const FLATPAK_SYSTEM: &str = "/var/lib/flatpak/app";
+const FLATPAK_LOCAL: &str = ".local/share/flatpak/app";
+
+#[derive(Debug, Default)]
+pub struct Installed {
+    /// Names of installed Debian packages.
+    pub packages: HashSet<String>,
+    /// App IDs of installed flatpaks, from both system and user installations.
+    pub flatpaks: HashSet<String>,
+}
+
+impl Installed {
+    pub fn load() -> Self {
+        let mut installed = Self::default();
+
+        if let Ok(status) = fs::read_to_string(DPKG_STATUS) {
+            installed.packages = parse_dpkg_status(&status);
+        }
+
+        read_flatpaks(Path::new(FLATPAK_SYSTEM), &mut installed.flatpaks);
+
+        if let Some(home) = env::var_os("HOME") {
+            read_flatpaks(&Path::new(&home).join(FLATPAK_LOCAL), &mut installed.flatpaks);
+        }
+
+        installed
+    }
+
+    /// Checks if a component is installed, either through its package or as a flatpak.
+    pub fn contains(&self, package: &str, id: &str) -> bool {
+        self.packages.contains(package)
+            || self.flatpaks.contains(id)
+            || self.flatpaks.contains(id.trim_end_matches(".desktop"))
+    }
+}
+
+fn parse_dpkg_status(status: &str) -> HashSet<String> {
+    let mut packages = HashSet::new();
+
+    for paragraph in status.split("\n\n") {
+        let mut package = None;
+        let mut installed = false;
+
+        for line in paragraph.lines() {
+            if let Some(name) = line.strip_prefix("Package: ") {
+                package = Some(name.trim());
+            } else if let Some(status) = line.strip_prefix("Status: ") {
+                installed = status.trim().ends_with(" installed");
+            }
+        }
+
+        if let (Some(package), true) = (package, installed) {
+            packages.insert(package.to_owned());
+        }
+    }
+
+    packages
+}
+
+fn read_flatpaks(path: &Path, flatpaks: &mut HashSet<String>) {
+    if let Ok(entries) = path.read_dir() {
+        for entry in entries.filter_map(Result::ok) {
+            if let Ok(name) = entry.file_name().into_string() {
+                flatpaks.insert(name);
+            }
+        }
+    }
+}
@@ -0,0 +1,75 @@
+//! Kinds of AppStream components, such as applications, fonts, and drivers.
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum ComponentKind {
+    DesktopApplication,
+    ConsoleApplication,
+    WebApplication,
+    Addon,
+    Font,
+    Codec,
+    InputMethod,
+    Driver,
+    Firmware,
+    Runtime,
+    Localization,
+    Service,
+    OperatingSystem,
+    Generic,
+    /// A kind which is not known, kept as it was written.
+    Unknown(String),
+}
+
+impl ComponentKind {
+    /// Kinds which users launch, and which are listed by default.
+    pub const APPS: &'static [ComponentKind] = &[
+        ComponentKind::DesktopApplication,
+        ComponentKind::ConsoleApplication,
+        ComponentKind::WebApplication,
+    ];
+
+    pub fn parse(kind: &str) -> Self {
+        match kind.to_ascii_lowercase().as_str() {
+            // `desktop` is the name of `desktop-application` in older appstream data
+            "desktop-application" | "desktop" => ComponentKind::DesktopApplication,
+            "console-application" => ComponentKind::ConsoleApplication,
+            "web-application" => ComponentKind::WebApplication,
+            "addon" => ComponentKind::Addon,
+            "font" => ComponentKind::Font,
+            "codec" => ComponentKind::Codec,
+            "inputmethod" => ComponentKind::InputMethod,
+            "driver" => ComponentKind::Driver,
+            "firmware" => ComponentKind::Firmware,
+            "runtime" => ComponentKind::Runtime,
+            "localization" => ComponentKind::Localization,
+            "service" => ComponentKind::Service,
+            "operating-system" => ComponentKind::OperatingSystem,
+            "generic" => ComponentKind::Generic,
+            _ => ComponentKind::Unknown(kind.to_owned())
+        }
+    }
+
+    pub fn as_str(&self) -> &str {
+        match self {
+            ComponentKind::DesktopApplication => "desktop-application",
+            ComponentKind::ConsoleApplication => "console-application",
+            ComponentKind::WebApplication => "web-application",
+            ComponentKind::Addon => "addon",
+            ComponentKind::Font => "font",
+            ComponentKind::Codec => "codec",
+            ComponentKind::InputMethod => "inputmethod",
+            ComponentKind::Driver => "driver",
+            ComponentKind::Firmware => "firmware",
+            ComponentKind::Runtime => "runtime",
+            ComponentKind::Localization => "localization",
+            ComponentKind::Service => "service",
+            ComponentKind::OperatingSystem => "operating-system",
+            ComponentKind::Generic => "generic",
+            ComponentKind::Unknown(kind) => kind,
+        }
+    }
+
+    pub fn is_app(&self) -> bool {
+        ComponentKind::APPS.contains(self)
+    }
+}
@@ -0,0 +1,97 @@
+//! Resolution of desktop IDs to the `.desktop` files of installed applications.
+
+use std::env;
+use std::path::{Path, PathBuf};
+
+const DATA_HOME: &str = ".local/share";
+const DATA_DIRS: &str = "/usr/local/share:/usr/share";
+
+/// Data directories to search for `.desktop` files, in order of precedence.
+pub fn data_dirs() -> Vec<PathBuf> {
+    let mut dirs = Vec::new();
+
+    match env::var_os("XDG_DATA_HOME").filter(|dir| !dir.is_empty()) {
+        Some(dir) => dirs.push(PathBuf::from(dir)),
+        None => if let Some(home) = env::var_os("HOME") {
+            dirs.push(Path::new(&home).join(DATA_HOME));
+        }
+    }
+
+    let data_dirs = env::var("XDG_DATA_DIRS")
+        .ok()
+        .filter(|dirs| !dirs.is_empty())
+        .unwrap_or_else(|| DATA_DIRS.to_owned());
+
+    dirs.extend(data_dirs.split(':').filter(|dir| !dir.is_empty()).map(PathBuf::from));
+    dirs
+}
+
+/// Finds the `.desktop` file of a desktop ID in the data directories.
+///
+/// As the desktop entry specification maps subdirectories of `applications` to prefixes
+/// separated by `-`, the ID `kde-kate.desktop` may also be found at
+/// `applications/kde/kate.desktop`.
+pub fn find_desktop_file(desktop_id: &str, dirs: &[PathBuf]) -> Option<PathBuf> {
+    let desktop_id = if desktop_id.ends_with(".desktop") {
+        desktop_id.to_owned()
+    } else {
+        [desktop_id, ".desktop"].concat()
+    };
+
+    dirs.iter().find_map(|dir| find_in(&dir.join("applications"), &desktop_id))
+}
+
+/// Finds a desktop ID beneath a directory, where any `-` may be a directory separator. Only
+/// the subdirectories which exist are descended into.
+fn find_in(dir: &Path, desktop_id: &str) -> Option<PathBuf> {
+    let path = dir.join(desktop_id);
+    if path.is_file() {
+        return Some(path);
+    }
+
+    desktop_id.match_indices('-').find_map(|(pos, _)| {
+        let subdir = dir.join(&desktop_id[..pos]);
+        if subdir.is_dir() {
+            find_in(&subdir, &desktop_id[pos + 1..])
+        } else {
+            None
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn prefixes_are_subdirectories() {
+        let root = env::temp_dir()
+            .join(format!("appstream-cache-launchable-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+
+        let (user, system) = (root.join("user"), root.join("system"));
+        for path in &[
+            "system/applications/kde/kate.desktop",
+            "system/applications/org.gnome.Maps.desktop",
+            "system/applications/a/b/c-d.desktop",
+            "user/applications/org.gnome.Maps.desktop",
+        ] {
+            let path = root.join(path);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, "[Desktop Entry]").unwrap();
+        }
+
+        let dirs = [user.clone(), system.clone()];
+        let find = |id: &str| find_desktop_file(id, &dirs);
+
+        assert_eq!(find("kde-kate.desktop"), Some(system.join("applications/kde/kate.desktop")));
+        assert_eq!(find("kde-kate"), Some(system.join("applications/kde/kate.desktop")));
+        assert_eq!(find("a-b-c-d.desktop"), Some(system.join("applications/a/b/c-d.desktop")));
+        assert_eq!(find("org.gnome.Maps"), Some(user.join("applications/org.gnome.Maps.desktop")));
+        assert_eq!(find("kde-konsole.desktop"), None);
+        assert_eq!(find("gnome-kate.desktop"), None);
+
+        let _ = fs::remove_dir_all(root);
+    }
+}
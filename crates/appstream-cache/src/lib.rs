@@ -1,23 +1,56 @@
 #[macro_use]
-extern crate anyhow;
-#[macro_use]
 extern crate serde;
 
+pub mod categories;
+pub mod commands;
+pub mod content_rating;
 pub mod dep11;
+pub mod developer;
 pub mod flatpak;
+pub mod hardware;
+pub mod installed;
+pub mod kind;
+pub mod launchable;
+pub mod license;
+pub mod locale;
+pub mod markup;
+pub mod provides;
+pub mod releases;
+pub mod screenshots;
+pub mod search;
+pub mod urls;
 pub mod yaml;
 
 pub use self::dep11::appstream::Dep11Package;
+pub use self::search::cursor::SearchCursor;
+use self::categories::CategoryNode;
+use self::commands::CommandCandidate;
+use self::content_rating::ContentRating;
+use self::developer::Developer;
+use self::hardware::Hardware;
+use self::installed::Installed;
+use self::kind::ComponentKind;
+use self::license::{Classification, License};
+use self::locale::{Locale, Translations};
+use self::markup::Description;
+use self::provides::ProvidedKind;
+use self::releases::{Release, ReleaseKind};
+use self::screenshots::Screenshot;
+use self::search::query::{Filter, Qualifier, Query};
+use self::search::{tokenize, Affinity, Document, Field, SearchIndex};
+use self::urls::UrlKind;
+use std::cmp::{Ordering, Reverse};
+use serde::de::DeserializeOwned;
 use std::rc::Rc;
 
 use std::path::{Path, PathBuf};
-use std::collections::{BTreeMap, HashMap};
+use std::collections::{BTreeMap, HashMap, HashSet};
 
 #[repr(u8)]
 pub enum PackageEvent {
     Dep11 {
         origin: String,
-        info: Dep11Package,
+        info: Box<Dep11Package>,
     },
 
     Dep11Icon {
@@ -34,16 +67,30 @@ pub enum PackageEvent {
 
 const KEY_TYPES: &str = "types";
 const KEY_IDS: &str = "ids";
+const KEY_COMPONENTS: &str = "components";
 const KEY_NAMES: &str = "names";
+const KEY_DISPLAY_NAMES: &str = "display-names";
 const KEY_ICONS: &str = "icons";
 const KEY_PACKAGES: &str = "packages";
 const KEY_SUMMARIES: &str = "summaries";
 const KEY_DESCRIPTIONS: &str = "descriptions";
-// const KEY_CATEGORIES: &str = "categories";
-// const KEY_KEYWORDS: &str = "keywords";
-// const KEY_LICENSES: &str = "licenses";
-// const KEY_URLS: &str = "urls";
-// const KEY_LAUNCHABLES: &str = "launchables";
+const KEY_CATEGORIES: &str = "categories";
+const KEY_CATEGORY_INDEX: &str = "category-index";
+const KEY_KEYWORDS: &str = "keywords";
+const KEY_LICENSES: &str = "licenses";
+const KEY_URLS: &str = "urls";
+const KEY_SCREENSHOTS: &str = "screenshots";
+const KEY_RELEASES: &str = "releases";
+const KEY_CONTENT_RATINGS: &str = "content-ratings";
+const KEY_PROVIDES: &str = "provides";
+const KEY_PROVIDES_INDEX: &str = "provides-index";
+const KEY_EXTENDS: &str = "extends";
+const KEY_ADDONS: &str = "addons";
+const KEY_DEVELOPERS: &str = "developers";
+const KEY_DEVELOPER_INDEX: &str = "developer-index";
+const KEY_PROJECT_GROUPS: &str = "project-groups";
+const KEY_PROJECT_GROUP_INDEX: &str = "project-group-index";
+const KEY_LAUNCHABLES: &str = "launchables";
 
 pub type Entity = u32;
 
@@ -53,25 +100,87 @@ pub struct OriginDb {
 
     pub types: sled::Tree,
     pub ids: sled::Tree,
+    pub components: sled::Tree,
     pub names: sled::Tree,
+    pub display_names: sled::Tree,
+    pub search: SearchIndex,
     pub icons: sled::Tree,
     pub packages: sled::Tree,
     pub summaries: sled::Tree,
+    pub descriptions: sled::Tree,
+    pub keywords: sled::Tree,
+    pub categories: sled::Tree,
+    pub category_index: sled::Tree,
+    pub licenses: sled::Tree,
+    pub urls: sled::Tree,
+    pub launchables: sled::Tree,
+    pub screenshots: sled::Tree,
+    pub releases: sled::Tree,
+    pub content_ratings: sled::Tree,
+    pub provides: sled::Tree,
+    pub provides_index: sled::Tree,
+    pub extends: sled::Tree,
+    pub addons: sled::Tree,
+    pub developers: sled::Tree,
+    pub developer_index: sled::Tree,
+    pub project_groups: sled::Tree,
+    pub project_group_index: sled::Tree,
 }
 
 impl OriginDb {
     pub fn new(origin: &str, db: &Path) -> Self {
         let db = sled::open(db.join(origin)).unwrap();
+
+        // Entities are numbered from zero on every refresh, so anything left in a tree by the
+        // last refresh would refer to a different component
         let _ = db.clear();
+        for name in db.tree_names() {
+            if let Ok(tree) = db.open_tree(name) {
+                let _ = tree.clear();
+            }
+        }
+
+        Self::with_db(db)
+    }
+
+    /// Opens an origin as it was left by the last refresh, so that it may be queried without
+    /// parsing the appstream data again.
+    pub fn open(origin: &str, db: &Path) -> sled::Result<Self> {
+        let mut origin = Self::with_db(sled::open(db.join(origin))?);
+        origin.id = origin.entities().max().map_or(0, |entity| entity + 1);
+        Ok(origin)
+    }
 
+    fn with_db(db: sled::Db) -> Self {
         Self {
             id: 0,
             types: db.open_tree(KEY_TYPES).unwrap(),
             ids: db.open_tree(KEY_IDS).unwrap(),
+            components: db.open_tree(KEY_COMPONENTS).unwrap(),
             names: db.open_tree(KEY_NAMES).unwrap(),
+            display_names: db.open_tree(KEY_DISPLAY_NAMES).unwrap(),
+            search: SearchIndex::open(&db),
             icons: db.open_tree(KEY_ICONS).unwrap(),
             packages: db.open_tree(KEY_PACKAGES).unwrap(),
             summaries: db.open_tree(KEY_SUMMARIES).unwrap(),
+            descriptions: db.open_tree(KEY_DESCRIPTIONS).unwrap(),
+            keywords: db.open_tree(KEY_KEYWORDS).unwrap(),
+            categories: db.open_tree(KEY_CATEGORIES).unwrap(),
+            category_index: db.open_tree(KEY_CATEGORY_INDEX).unwrap(),
+            licenses: db.open_tree(KEY_LICENSES).unwrap(),
+            urls: db.open_tree(KEY_URLS).unwrap(),
+            launchables: db.open_tree(KEY_LAUNCHABLES).unwrap(),
+            screenshots: db.open_tree(KEY_SCREENSHOTS).unwrap(),
+            releases: db.open_tree(KEY_RELEASES).unwrap(),
+            content_ratings: db.open_tree(KEY_CONTENT_RATINGS).unwrap(),
+            provides: db.open_tree(KEY_PROVIDES).unwrap(),
+            provides_index: db.open_tree(KEY_PROVIDES_INDEX).unwrap(),
+            extends: db.open_tree(KEY_EXTENDS).unwrap(),
+            addons: db.open_tree(KEY_ADDONS).unwrap(),
+            developers: db.open_tree(KEY_DEVELOPERS).unwrap(),
+            developer_index: db.open_tree(KEY_DEVELOPER_INDEX).unwrap(),
+            project_groups: db.open_tree(KEY_PROJECT_GROUPS).unwrap(),
+            project_group_index: db.open_tree(KEY_PROJECT_GROUP_INDEX).unwrap(),
             db
         }
     }
@@ -87,32 +196,195 @@ impl OriginDb {
             .and_then(|ivec| String::from_utf8(ivec.to_vec()).ok())
     }
 
-    pub fn add_dep11_package(&mut self, package: Dep11Package, language: &str) {
-        let id = &self.id.to_ne_bytes();
+    /// Stores every translation of the package, and indexes it in the given locale.
+    pub fn add_dep11_package(&mut self, package: Dep11Package, locale: &Locale) {
+        let entity = self.id;
+        let id = &entity.to_ne_bytes();
         self.id += 1;
 
-        if let Some(name) = package.name.get(language).or_else(|| package.name.get("C")) {
-            let _ = self.names.insert(name.as_bytes(), id);
-            let _ = self.ids.insert(id, package.id.as_bytes());
-            let _ = self.types.insert(id, package.type_.as_bytes());
-            let _ = self.packages.insert(id, package.package.as_bytes());
+        let names = locale::normalize_keys(package.name);
+        if names.is_empty() {
+            return;
+        }
+
+        let _ = self.display_names.insert(id, bincode::serialize(&names).unwrap());
+        let _ = self.ids.insert(id, package.id.as_bytes());
+        let _ = self.components.insert(package.id.as_bytes(), id);
+        let _ = self.types.insert(id, ComponentKind::parse(&package.type_).as_str().as_bytes());
+        let _ = self.packages.insert(id, package.package.as_bytes());
+
+        let summaries = locale::normalize_keys(package.summary);
+        let _ = self.summaries.insert(id, bincode::serialize(&summaries).unwrap());
+
+        if let Some(descriptions) = package.description {
+            let descriptions = locale::normalize_keys(descriptions);
+            let _ = self.descriptions.insert(id, bincode::serialize(&descriptions).unwrap());
+        }
+
+        if let Some(keywords) = package.keywords {
+            let keywords = locale::normalize_keys(keywords);
+            let _ = self.keywords.insert(id, bincode::serialize(&keywords).unwrap());
+        }
+
+        if let Some(categories) = package.categories.as_ref() {
+            let _ = self.categories.insert(id, bincode::serialize(categories).unwrap());
+
+            for category in categories {
+                let _ = self.category_index.insert(multimap_key(category.as_bytes(), entity), id);
+            }
+        }
+
+        if let Some(license) = package.license.as_ref() {
+            let _ = self.licenses.insert(id, license.as_bytes());
+        }
+
+        if let Some(urls) = package.urls.as_ref() {
+            let _ = self.urls.insert(id, bincode::serialize(urls).unwrap());
+        }
+
+        if let Some(launchable) = package.launchable.as_ref() {
+            let desktop_ids = &launchable.desktop_id;
+            let _ = self.launchables.insert(id, bincode::serialize(desktop_ids).unwrap());
+        }
+
+        if let Some(mut screenshots) = package.screenshots {
+            for screenshot in &mut screenshots {
+                if let Some(caption) = screenshot.caption.take() {
+                    screenshot.caption = Some(locale::normalize_keys(caption));
+                }
+            }
+
+            let _ = self.screenshots.insert(id, bincode::serialize(&screenshots).unwrap());
+        }
+
+        if let Some(releases) = package.releases {
+            let mut releases: Vec<Release> = releases.into_iter().map(Release::from).collect();
+            releases::sort(&mut releases);
+            let _ = self.releases.insert(id, bincode::serialize(&releases).unwrap());
+        }
+
+        if let Some(rating) = package.content_rating.and_then(ContentRating::from_dep11) {
+            let _ = self.content_ratings.insert(id, bincode::serialize(&rating).unwrap());
+        }
+
+        if let Some(provides) = package.provides {
+            let provides = provides::flatten(provides);
+            let _ = self.provides.insert(id, bincode::serialize(&provides).unwrap());
+
+            for (kind, value) in &provides {
+                let _ = self.provides_index.insert(multimap_key(&kind.index_key(value), entity), id);
+            }
+        }
+
+        if let Some(extends) = package.extends.as_ref() {
+            let _ = self.extends.insert(id, bincode::serialize(extends).unwrap());
+
+            for parent in extends {
+                let _ = self.addons.insert(multimap_key(parent.as_bytes(), entity), id);
+            }
+        }
+
+        let (developer_id, developer_name) = match package.developer {
+            Some(developer) => (developer.id, developer.name.or(package.developer_name)),
+            None => (None, package.developer_name)
+        };
+
+        if let Some(developer) = Developer::new(developer_id, developer_name) {
+            let _ = self.developers.insert(id, bincode::serialize(&developer).unwrap());
+
+            for key in developer.index_keys() {
+                let _ = self.developer_index.insert(multimap_key(key.as_bytes(), entity), id);
+            }
+        }
+
+        if let Some(group) = package.project_group.as_ref().filter(|group| !group.is_empty()) {
+            let _ = self.project_groups.insert(id, group.as_bytes());
+            let key = search::normalize::fold(group);
+            let _ = self.project_group_index.insert(multimap_key(key.as_bytes(), entity), id);
+        }
+
+        if let Some(icon) = package.icon {
+            let _ = self.icons.insert(id, bincode::serialize(&icon).unwrap());
+        }
+
+        self.index(entity, locale);
+    }
+
+    /// Rebuilds the name and search indexes in another locale, from the translations which
+    /// were stored when the origin was refreshed.
+    pub fn reindex(&self, locale: &Locale) {
+        let _ = self.names.clear();
+        self.search.clear();
+
+        for entity in self.entities() {
+            self.index(entity, locale);
+        }
+    }
+
+    fn index(&self, entity: Entity, locale: &Locale) {
+        let names = self.translations(&self.display_names, entity);
+        if locale.resolve(&names).is_none() {
+            return;
+        }
+
+        // Every translation of the name is searchable, but translations in the languages of
+        // the user's locale are ranked above untranslated names, which are above the rest.
+        let mut affinities: HashMap<&str, Affinity> = HashMap::new();
+        for (language, name) in &names {
+            let affinity = Affinity::of(locale, language);
+            let best = affinities.entry(name.as_str()).or_insert(affinity);
+            *best = affinity.max(*best);
+        }
+
+        let mut document = Document::default();
 
-            if let Some(summary) = package.summary.get(language).or_else(|| package.name.get("C")) {
-                let _ = self.summaries.insert(id, summary.as_bytes());
+        for (name, affinity) in affinities {
+            let _ = self.names.insert(multimap_key(name.as_bytes(), entity), &entity.to_ne_bytes());
+
+            if affinity != Affinity::Foreign {
+                self.search.insert_completion(name, entity);
             }
 
-            if let Some(icon) = package.icon {
-                let _ = self.icons.insert(id, bincode::serialize(&icon).unwrap());
+            document.add(affinity.name_field(), name);
+        }
+
+        if let Some(summary) = self.summary(entity, locale) {
+            document.add(Field::Summary, &summary);
+        }
+
+        for (language, keywords) in self.all_keywords(entity) {
+            let field = Affinity::of(locale, &language).keywords_field();
+
+            for keyword in keywords {
+                document.add(field, &keyword);
             }
         }
+
+        if let Some(description) = self.description(entity, locale) {
+            document.add(Field::Description, &Description::parse(&description).to_plain_text());
+        }
+
+        for category in self.categories(entity) {
+            document.add(Field::Categories, &category);
+        }
+
+        self.search.insert(&document, entity);
     }
 
     pub fn id(&self, package: Entity) -> Option<String> {
         self.fetch_string(&self.ids, package)
     }
 
+    /// Looks up the entity which this origin assigned to a component ID.
+    pub fn entity(&self, id: &str) -> Option<Entity> {
+        let ivec = self.components.get(id.as_bytes()).ok().flatten()?;
+        let mut entity = [0u8; 4];
+        entity.copy_from_slice(&ivec[..4]);
+        Some(u32::from_ne_bytes(entity))
+    }
+
     pub fn icon(&self, package: Entity) -> Option<String> {
-        if let Some(icon) = self.icons.get(&package.to_ne_bytes()).ok().flatten() {
+        if let Some(icon) = self.icons.get(package.to_ne_bytes()).ok().flatten() {
             if let Ok(icon) = bincode::deserialize::<dep11::appstream::Icon>(&icon) {
                 if let Some(cached) = icon.cached {
                     if let Some(cached) = cached.first() {
@@ -125,24 +397,219 @@ impl OriginDb {
         None
     }
 
-    pub fn summary(&self, package: Entity) -> Option<String> {
-        self.fetch_string(&self.summaries, package)
+    pub fn name(&self, package: Entity, locale: &Locale) -> Option<String> {
+        self.fetch_localized(&self.display_names, package, locale)
     }
 
-    pub fn iter(&self, mut fun: impl FnMut(Entity, &str)) {
-        for (id, key) in self.names.iter().filter_map(Result::ok) {
-            if let Ok(id) = std::str::from_utf8(&id) {
-                let mut entity = [0u8; 4];
-                entity.copy_from_slice(&key[..4]);
-                let entity = u32::from_ne_bytes(entity);
+    pub fn summary(&self, package: Entity, locale: &Locale) -> Option<String> {
+        self.fetch_localized(&self.summaries, package, locale)
+    }
+
+    /// The description of a component, in AppStream's description markup.
+    pub fn description(&self, package: Entity, locale: &Locale) -> Option<String> {
+        self.fetch_localized(&self.descriptions, package, locale)
+    }
+
+    /// Keywords of a component in the most preferred language which has any.
+    pub fn keywords(&self, package: Entity, locale: &Locale) -> Vec<String> {
+        locale.resolve(&self.all_keywords(package)).cloned().unwrap_or_default()
+    }
+
+    /// Keywords of a component in every language, keyed by language.
+    pub fn all_keywords(&self, package: Entity) -> HashMap<String, Vec<String>> {
+        self.fetch_bincode(&self.keywords, package).unwrap_or_default()
+    }
+
+    /// Every translation stored in a localized tree, such as `display_names`.
+    pub fn translations(&self, db: &sled::Tree, package: Entity) -> Translations {
+        self.fetch_bincode(db, package).unwrap_or_default()
+    }
+
+    pub fn package(&self, package: Entity) -> Option<String> {
+        self.fetch_string(&self.packages, package)
+    }
+
+    pub fn component_type(&self, package: Entity) -> Option<String> {
+        self.fetch_string(&self.types, package)
+    }
+
+    pub fn kind(&self, package: Entity) -> Option<ComponentKind> {
+        self.component_type(package).map(|kind| ComponentKind::parse(&kind))
+    }
+
+    pub fn categories(&self, package: Entity) -> Vec<String> {
+        self.fetch_bincode(&self.categories, package).unwrap_or_default()
+    }
+
+    /// Every entity in this origin which is in the given category.
+    pub fn entities_in(&self, category: &str) -> Vec<Entity> {
+        self.scan_multimap(&self.category_index, category.as_bytes())
+    }
+
+    /// The SPDX license expression of a component, as it was written.
+    pub fn license(&self, package: Entity) -> Option<String> {
+        self.fetch_string(&self.licenses, package)
+    }
+
+    pub fn license_expression(&self, package: Entity) -> Option<License> {
+        self.license(package).and_then(|expression| License::parse(&expression))
+    }
+
+    /// Links to the project's resources, sorted by kind. URLs of kinds which are not known
+    /// are skipped.
+    pub fn urls(&self, package: Entity) -> Vec<(UrlKind, String)> {
+        let urls: HashMap<String, String> = self.fetch_bincode(&self.urls, package)
+            .unwrap_or_default();
+
+        let mut urls: Vec<(UrlKind, String)> = urls.into_iter()
+            .filter_map(|(kind, url)| Some((UrlKind::parse(&kind)?, url)))
+            .collect();
+
+        urls.sort();
+        urls
+    }
+
+    pub fn url(&self, package: Entity, kind: UrlKind) -> Option<String> {
+        self.urls(package).into_iter().find(|(k, _)| *k == kind).map(|(_, url)| url)
+    }
+
+    /// Desktop IDs of the applications which launch a component.
+    pub fn launchables(&self, package: Entity) -> Vec<String> {
+        self.fetch_bincode(&self.launchables, package).unwrap_or_default()
+    }
+
+    /// Screenshots of a component with the default screenshot first, and their URLs resolved
+    /// against the media base URL of this origin.
+    pub fn screenshots(&self, package: Entity) -> Vec<Screenshot> {
+        let mut screenshots: Vec<Screenshot> = self.fetch_bincode(&self.screenshots, package)
+            .unwrap_or_default();
+
+        let base_url = self.media_url();
+        for screenshot in &mut screenshots {
+            screenshot.resolve(base_url.as_deref());
+        }
+
+        screenshots.sort_by_key(|screenshot| !screenshot.is_default());
+        screenshots
+    }
+
+    /// Releases of a component, from newest to oldest.
+    pub fn releases(&self, package: Entity) -> Vec<Release> {
+        self.fetch_bincode(&self.releases, package).unwrap_or_default()
+    }
+
+    /// The newest stable release of a component.
+    pub fn latest_release(&self, package: Entity) -> Option<Release> {
+        self.releases(package).into_iter().find(|release| release.kind == ReleaseKind::Stable)
+    }
 
-                fun(entity, id)
+    pub fn content_rating(&self, package: Entity) -> Option<ContentRating> {
+        self.fetch_bincode(&self.content_ratings, package)
+    }
+
+    /// Everything that a component provides, such as its binaries and media types.
+    pub fn provides(&self, package: Entity) -> Vec<(ProvidedKind, String)> {
+        self.fetch_bincode(&self.provides, package).unwrap_or_default()
+    }
+
+    /// Every entity in this origin which provides the given item.
+    pub fn provided_by(&self, kind: ProvidedKind, value: &str) -> Vec<Entity> {
+        self.scan_multimap(&self.provides_index, &kind.index_key(value))
+    }
+
+    /// Every item of a kind which is provided in this origin, along with its provider.
+    pub fn provided(&self, kind: ProvidedKind) -> Vec<(String, Entity)> {
+        let prefix = kind.index_key("");
+
+        self.provides_index.scan_prefix(&prefix)
+            .keys()
+            .filter_map(Result::ok)
+            .filter_map(|key| {
+                let (value, entity) = split_multimap_key(&key)?;
+                let value = std::str::from_utf8(&value[prefix.len()..]).ok()?;
+                Some((value.to_owned(), entity))
+            })
+            .collect()
+    }
+
+    /// IDs of the components which an addon extends.
+    pub fn extends(&self, package: Entity) -> Vec<String> {
+        self.fetch_bincode(&self.extends, package).unwrap_or_default()
+    }
+
+    /// Every entity in this origin which extends the component with the given ID.
+    pub fn addons_of(&self, id: &str) -> Vec<Entity> {
+        self.scan_multimap(&self.addons, id.as_bytes())
+    }
+
+    pub fn developer(&self, package: Entity) -> Option<Developer> {
+        self.fetch_bincode(&self.developers, package)
+    }
+
+    /// The project that a component is part of, such as `GNOME` or `KDE`.
+    pub fn project_group(&self, package: Entity) -> Option<String> {
+        self.fetch_string(&self.project_groups, package)
+    }
+
+    /// Every entity in this origin by a developer, given by either their ID or name.
+    pub fn entities_by(&self, developer: &str) -> Vec<Entity> {
+        self.scan_multimap(&self.developer_index, search::normalize::fold(developer).as_bytes())
+    }
+
+    /// Every entity in this origin which is part of a project group.
+    pub fn entities_in_group(&self, group: &str) -> Vec<Entity> {
+        self.scan_multimap(&self.project_group_index, search::normalize::fold(group).as_bytes())
+    }
+
+    /// Every entity stored in this origin.
+    pub fn entities(&self) -> impl Iterator<Item = Entity> {
+        self.ids.iter().keys().filter_map(Result::ok).map(|key| {
+            let mut entity = [0u8; 4];
+            entity.copy_from_slice(&key[..4]);
+            u32::from_ne_bytes(entity)
+        })
+    }
+
+    /// Every entity in this origin with the given name.
+    pub fn entities_named(&self, name: &str) -> Vec<Entity> {
+        self.scan_multimap(&self.names, name.as_bytes())
+    }
+
+    pub fn iter(&self, mut fun: impl FnMut(Entity, &str)) {
+        for key in self.names.iter().keys().filter_map(Result::ok) {
+            if let Some((name, entity)) = split_multimap_key(&key) {
+                if let Ok(name) = std::str::from_utf8(name) {
+                    fun(entity, name)
+                }
             }
         }
     }
 
+    fn fetch_localized(&self, db: &sled::Tree, package: Entity, locale: &Locale) -> Option<String> {
+        locale.resolve(&self.translations(db, package)).cloned()
+    }
+
+    /// Every entity stored under a value in a multimap tree.
+    fn scan_multimap(&self, db: &sled::Tree, value: &[u8]) -> Vec<Entity> {
+        let mut prefix = value.to_vec();
+        prefix.push(0);
+
+        db.scan_prefix(&prefix)
+            .keys()
+            .filter_map(Result::ok)
+            .filter_map(|key| split_multimap_key(&key).map(|(_, entity)| entity))
+            .collect()
+    }
+
+    fn fetch_bincode<T: DeserializeOwned>(&self, db: &sled::Tree, package: Entity) -> Option<T> {
+        db.get(package.to_ne_bytes())
+            .ok()
+            .flatten()
+            .and_then(|ivec| bincode::deserialize(&ivec).ok())
+    }
+
     fn fetch_string(&self, db: &sled::Tree, package: Entity) -> Option<String> {
-        if let Some(ivec) = db.get(&package.to_ne_bytes()).ok().flatten() {
+        if let Some(ivec) = db.get(package.to_ne_bytes()).ok().flatten() {
             if let Ok(string) = std::str::from_utf8(&ivec) {
                 return Some(String::from(string))
             }
@@ -153,17 +620,186 @@ impl OriginDb {
 }
 
 
+/// Keys of multimap trees are the indexed value, a null separator, and then the entity, so that
+/// entities sharing the same value are all stored rather than overwriting each other.
+fn multimap_key(value: &[u8], entity: Entity) -> Vec<u8> {
+    let mut key = Vec::with_capacity(value.len() + 5);
+    key.extend_from_slice(value);
+    key.push(0);
+    key.extend_from_slice(&entity.to_ne_bytes());
+    key
+}
+
+fn split_multimap_key(key: &[u8]) -> Option<(&[u8], Entity)> {
+    if key.len() < 5 || key[key.len() - 5] != 0 {
+        return None;
+    }
+
+    let (value, entity) = key.split_at(key.len() - 5);
+    let mut bytes = [0u8; 4];
+    bytes.copy_from_slice(&entity[1..]);
+    Some((value, u32::from_ne_bytes(bytes)))
+}
+
+/// A single application, merged from every origin which provides its component ID.
+#[derive(Debug, Clone)]
+pub struct AppEntry {
+    pub id: String,
+    /// Name of the app, as given by the most preferred origin.
+    pub name: String,
+    /// Each origin providing the app, sorted by the database's origin preference.
+    pub variants: Vec<(Rc<str>, Entity)>,
+}
+
+impl AppEntry {
+    /// The variant from the most preferred origin.
+    pub fn preferred(&self) -> (&Rc<str>, Entity) {
+        let (origin, entity) = &self.variants[0];
+        (origin, *entity)
+    }
+}
+
 pub struct Database {
     pub path: PathBuf,
-    pub language: String,
+    pub locale: Locale,
     pub icons: sled::Db,
     pub origins: BTreeMap<String, OriginDb>,
+    pub installed: Installed,
+
+    /// Origins to prefer when the same app is provided by more than one origin.
+    /// Origins which are not listed rank below those that are.
+    pub origin_preference: Vec<String>,
+
+    /// Hides components from search and browse results unless their license is approved by
+    /// either the FSF or the OSI.
+    pub approved_licenses_only: bool,
+
+    /// Hides components from search and browse results if their content rating is unsuitable
+    /// for this age. Components without a content rating are not hidden, as most software
+    /// which is not a game does not provide one.
+    pub age_limit: Option<u8>,
+
+    /// Kinds of components listed in search and browse results, or every kind if `None`.
+    /// A search for a kind through the `kind:` qualifier overrides this.
+    pub kinds: Option<Vec<ComponentKind>>,
 }
 
 impl Database {
-    pub fn new(path: PathBuf, language: String) -> Self {
-        let icons = sled::open(path.join("icons")).unwrap();
-        Self { path, origins: BTreeMap::new(), icons, language }
+    pub fn new(path: PathBuf, locale: Locale) -> Self {
+        Self::open(path, locale).unwrap()
+    }
+
+    /// Opens the database without panicking, such as when another process holds its lock.
+    pub fn open(path: PathBuf, locale: Locale) -> anyhow::Result<Self> {
+        let icons = sled::open(path.join("icons"))?;
+        Ok(Self {
+            path,
+            origins: BTreeMap::new(),
+            icons,
+            locale,
+            installed: Installed::load(),
+            origin_preference: Vec::new(),
+            approved_licenses_only: false,
+            age_limit: None,
+            kinds: None,
+        })
+    }
+
+    /// Switches the language of names and search results, without refreshing the origins.
+    pub fn set_locale(&mut self, locale: Locale) {
+        if locale != self.locale {
+            for db in self.origins.values() {
+                db.reindex(&locale);
+            }
+
+            self.locale = locale;
+        }
+    }
+
+    pub fn set_approved_licenses_only(&mut self, approved_only: bool) {
+        self.approved_licenses_only = approved_only;
+    }
+
+    pub fn set_age_limit(&mut self, age: Option<u8>) {
+        self.age_limit = age;
+    }
+
+    pub fn set_kinds(&mut self, kinds: Option<Vec<ComponentKind>>) {
+        self.kinds = kinds;
+    }
+
+    pub fn set_origin_preference(&mut self, origins: Vec<String>) {
+        self.origin_preference = origins;
+    }
+
+    fn origin_rank(&self, origin: &str) -> usize {
+        self.origin_preference.iter()
+            .position(|preferred| preferred == origin)
+            .unwrap_or(self.origin_preference.len())
+    }
+
+    /// Every origin that provides the given component ID, sorted by origin preference.
+    pub fn variants(&self, id: &str) -> Vec<(Rc<str>, Entity)> {
+        let mut variants: Vec<(Rc<str>, Entity)> = self.origins.iter()
+            .filter_map(|(origin, db)| db.entity(id).map(|entity| (Rc::from(origin.as_str()), entity)))
+            .collect();
+
+        variants.sort_by_key(|(origin, _)| self.origin_rank(origin));
+        variants
+    }
+
+    /// Collapses matches of the same component ID across origins into a single entry per app,
+    /// keeping the order in which each app was first matched. Each entry lists every origin
+    /// which provides the app, including those which did not match.
+    pub fn merge(&self, matches: Vec<(Rc<str>, Entity)>) -> Vec<AppEntry> {
+        self.group(matches)
+            .into_iter()
+            .filter_map(|id| self.app_entry(id))
+            .collect()
+    }
+
+    /// Component IDs of the matches, in the order in which each was first matched.
+    fn group(&self, matches: Vec<(Rc<str>, Entity)>) -> Vec<String> {
+        let mut ids: Vec<String> = Vec::new();
+        let mut seen: HashSet<String> = HashSet::new();
+
+        for (origin, entity) in matches {
+            if let Some(id) = self.origins.get(&*origin).and_then(|db| db.id(entity)) {
+                if seen.insert(id.clone()) {
+                    ids.push(id);
+                }
+            }
+        }
+
+        ids
+    }
+
+    /// Creates the entry of an app from every origin which provides it, named by its most
+    /// preferred variant.
+    pub(crate) fn app_entry(&self, id: String) -> Option<AppEntry> {
+        let variants = self.variants(&id);
+        let (origin, entity) = variants.first()?;
+        let name = self.origins.get(&**origin)?.name(*entity, &self.locale)?;
+        Some(AppEntry { id, name, variants })
+    }
+
+    /// Opens the origins stored by the last refresh, rather than refreshing them.
+    pub fn open_cached(&mut self) -> anyhow::Result<()> {
+        for entry in self.path.read_dir()?.filter_map(Result::ok) {
+            if !entry.path().is_dir() {
+                continue
+            }
+
+            let origin = match entry.file_name().into_string() {
+                Ok(origin) if origin != "icons" => origin,
+                _ => continue
+            };
+
+            let db = OriginDb::open(&origin, &self.path)?;
+            self.origins.insert(origin, db);
+        }
+
+        Ok(())
     }
 
     pub fn get_origin(&mut self, origin: &str) -> &mut OriginDb {
@@ -179,6 +815,9 @@ impl Database {
     }
 
     pub async fn refresh_appstream_components(&mut self) -> anyhow::Result<()> {
+        // Origins are rebuilt from scratch, rather than appended to
+        self.origins.clear();
+
         // Each package list is going to contain a stream of packages we'll collate
         let (tx, rx) = smol::channel::unbounded();
 
@@ -191,12 +830,12 @@ impl Database {
             dep11::fetch(executor, tx.clone())?;
             flatpak::fetch(executor, tx)?;
 
-            let language = self.language.clone();
+            let locale = self.locale.clone();
 
             while let Ok(event) = rx.recv().await {
                 match event {
                     PackageEvent::Dep11 { origin, info } => {
-                        self.get_origin(&origin).add_dep11_package(info, &language);
+                        self.get_origin(&origin).add_dep11_package(*info, &locale);
                     }
 
                     PackageEvent::Dep11Icon { size, name, buffer } => {
@@ -211,26 +850,846 @@ impl Database {
 
             let _ = self.flush().await;
 
+            self.installed = Installed::load();
+
             Ok(())
         }).await
     }
 
+    /// Searches the full-text index of every origin, with the most relevant results first.
+    ///
+    /// The query may contain qualifiers, as described in `search::query`. If the free text of
+    /// the query matches nothing, the results of its spelling suggestion are returned instead.
     pub async fn search_for(&self, package: &str) -> Vec<(Rc<str>, Entity, String)> {
+        self.matches(package, true).0
+            .into_iter()
+            .filter_map(|(origin, entity)| {
+                let name = self.origins.get(&*origin)?.name(entity, &self.locale)?;
+                Some((origin, entity, name))
+            })
+            .collect()
+    }
+
+    /// Searches for apps in the same manner as `search_for`, with one result per app regardless
+    /// of how many origins provide it. Results are fetched from the cursor a page at a time.
+    ///
+    /// If the results are those of a spelling suggestion, the cursor holds the suggestion so
+    /// that it may be shown, and `search_verbatim` searches for the query as it was typed.
+    pub async fn search(&self, query: &str) -> SearchCursor {
+        let (matches, suggestion) = self.matches(query, true);
+        SearchCursor::with_suggestion(self.group(matches), suggestion)
+    }
+
+    /// Searches for apps without correcting the spelling of the query.
+    pub async fn search_verbatim(&self, query: &str) -> SearchCursor {
+        SearchCursor::new(self.group(self.matches(query, false).0))
+    }
+
+    /// Ranks the matches of a query, along with the corrected free text of the query if it
+    /// matched nothing and `correct` is set.
+    fn matches(&self, input: &str, correct: bool) -> (Vec<(Rc<str>, Entity)>, Option<String>) {
+        let query = Query::parse(input);
+        let matches = self.ranked(&query);
+
+        if correct && matches.is_empty() && !query.text.is_empty() {
+            if let Some(suggestion) = self.suggest(&query.text) {
+                let matches = self.ranked(&Query { text: suggestion.clone(), ..query });
+                return (matches, Some(suggestion));
+            }
+        }
+
+        (matches, None)
+    }
+
+    /// Corrects each term of the query which matches nothing in any origin against the name and
+    /// keyword vocabulary of every origin. Returns `None` if there was nothing to correct.
+    pub fn suggest(&self, query: &str) -> Option<String> {
+        let terms = tokenize(query);
+        let mut corrected = false;
+
+        let suggestion: Vec<String> = terms.into_iter()
+            .map(|term| {
+                if self.origins.values().any(|db| db.search.contains(&term)) {
+                    return term;
+                }
+
+                let best = self.origins.values()
+                    .filter_map(|db| db.search.correct(&term))
+                    .min_by_key(|(_, distance, frequency)| (*distance, Reverse(*frequency)));
+
+                match best {
+                    Some((replacement, _, _)) => {
+                        corrected = true;
+                        replacement
+                    }
+                    None => term
+                }
+            })
+            .collect();
+
+        if corrected {
+            Some(suggestion.join(" "))
+        } else {
+            None
+        }
+    }
+
+    /// Completions for a partially typed query, for suggesting as the user types.
+    ///
+    /// Names which begin with the input come first, followed by the input with its last term
+    /// completed from the name and keyword vocabulary.
+    pub fn complete(&self, input: &str, limit: usize) -> Vec<String> {
+        let mut completions: Vec<String> = Vec::with_capacity(limit);
+
+        let mut names: Vec<String> = self.origins.values()
+            .flat_map(|db| {
+                db.search.complete_name(input, limit, |entity| self.is_visible(db, entity))
+            })
+            .map(|(name, _)| name)
+            .collect();
+
+        names.sort_by_cached_key(|name| search::normalize::fold(name));
+        names.dedup();
+        names.truncate(limit);
+        completions.extend(names);
+
+        // Qualifiers are not completed, and neither is a term that has been finished
+        let partial = input.split_whitespace().last().unwrap_or("");
+        if input.ends_with(char::is_whitespace) || partial.contains(':') {
+            return completions;
+        }
+
+        let mut terms = tokenize(input);
+        let last = match terms.pop() {
+            Some(last) => last,
+            None => return completions
+        };
+
+        let mut frequencies: HashMap<String, u32> = HashMap::new();
+        for db in self.origins.values() {
+            for (term, count) in db.search.complete_term(&last, limit) {
+                *frequencies.entry(term).or_insert(0) += count;
+            }
+        }
+
+        let mut frequencies: Vec<(String, u32)> = frequencies.into_iter().collect();
+        frequencies.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+
+        for (term, _) in frequencies {
+            if completions.len() >= limit {
+                break
+            }
+
+            // The input itself is not a completion of the input
+            if term == last {
+                continue
+            }
+
+            let mut completion = terms.join(" ");
+            if !completion.is_empty() {
+                completion.push(' ');
+            }
+            completion.push_str(&term);
+
+            let folded = search::normalize::fold(&completion);
+            if !completions.iter().any(|c| search::normalize::fold(c) == folded) {
+                completions.push(completion);
+            }
+        }
+
+        completions
+    }
+
+    fn ranked(&self, query: &Query) -> Vec<(Rc<str>, Entity)> {
         let mut packages = Vec::new();
 
+        // Searching for a kind lists it even if the database does not list it by default
+        let any_kind = query.filters.iter()
+            .any(|filter| !filter.negated && matches!(filter.qualifier, Qualifier::Type(_)));
+
         for (origin, origin_db) in &self.origins {
+            let origin_allowed = query.filters.iter().all(|filter| match &filter.qualifier {
+                Qualifier::Origin(name) => filter.negated != (name == origin),
+                _ => true
+            });
+
+            if !origin_allowed {
+                continue
+            }
+
+            let candidates: HashMap<Entity, f32> = if query.text.is_empty() {
+                origin_db.entities().map(|entity| (entity, 0.0)).collect()
+            } else {
+                origin_db.search.query(&query.text)
+            };
+
+            let excluded: Vec<HashMap<Entity, f32>> = query.excluded.iter()
+                .map(|term| origin_db.search.query_exact(term))
+                .collect();
+
+            let origin: Rc<str> = Rc::from(origin.as_str());
+            for (entity, score) in candidates {
+                if excluded.iter().any(|matches| matches.contains_key(&entity)) {
+                    continue
+                }
+
+                if !self.is_permitted(origin_db, entity) {
+                    continue
+                }
+
+                if !any_kind && !self.is_listed_kind(origin_db, entity) {
+                    continue
+                }
+
+                if query.filters.iter().all(|filter| self.satisfies(origin_db, entity, filter)) {
+                    packages.push((score, origin.clone(), entity));
+                }
+            }
+        }
+
+        packages.sort_by(|a, b| {
+            b.0.partial_cmp(&a.0)
+                .unwrap_or(Ordering::Equal)
+                .then_with(|| self.origin_rank(&a.1).cmp(&self.origin_rank(&b.1)))
+                .then_with(|| a.2.cmp(&b.2))
+        });
+
+        packages.into_iter()
+            .map(|(_, origin, entity)| (origin, entity))
+            .collect()
+    }
+
+    fn satisfies(&self, db: &OriginDb, entity: Entity, filter: &Filter) -> bool {
+        let matched = match &filter.qualifier {
+            Qualifier::Type(kind) => db.kind(entity).is_some_and(|k| {
+                match kind.to_ascii_lowercase().as_str() {
+                    "app" | "apps" => k.is_app(),
+                    _ => k.as_str().eq_ignore_ascii_case(ComponentKind::parse(kind).as_str())
+                }
+            }),
+            Qualifier::Category(category) => db.categories(entity)
+                .iter()
+                .any(|c| c.eq_ignore_ascii_case(category)),
+            Qualifier::License(license) => {
+                db.license_expression(entity).is_some_and(|expression| {
+                    match license.to_ascii_lowercase().as_str() {
+                        "free" => expression.classify() == Classification::Free,
+                        "open-source" => expression.approval().1,
+                        "proprietary" => expression.classify() == Classification::Proprietary,
+                        "approved" => expression.is_approved(),
+                        _ => expression.mentions(license)
+                    }
+                })
+            }
+            // Origins are filtered before their entities are visited
+            Qualifier::Origin(_) => return true,
+            Qualifier::Installed(installed) => self.is_installed(db, entity) == *installed,
+        };
+
+        matched != filter.negated
+    }
+
+    /// Apps in a category, sorted by name, fetched a page at a time.
+    pub fn browse(&self, category: &str) -> SearchCursor {
+        self.alphabetical(|db| db.entities_in(category))
+    }
+
+    /// Apps by a developer, given by either their ID or name, for "More from this developer".
+    /// Apps are sorted by name, and fetched a page at a time.
+    pub fn by_developer(&self, developer: &str) -> SearchCursor {
+        self.alphabetical(|db| db.entities_by(developer))
+    }
+
+    /// Apps in a project group, such as `GNOME` or `KDE`, sorted by name.
+    pub fn in_project_group(&self, group: &str) -> SearchCursor {
+        self.alphabetical(|db| db.entities_in_group(group))
+    }
+
+    /// Lists the visible entities that each origin selects, sorted by name.
+    fn alphabetical(&self, select: impl Fn(&OriginDb) -> Vec<Entity>) -> SearchCursor {
+        let mut matches: Vec<(String, Rc<str>, Entity)> = Vec::new();
+
+        for (origin, db) in &self.origins {
+            let origin: Rc<str> = Rc::from(origin.as_str());
+            for entity in select(db) {
+                if !self.is_visible(db, entity) {
+                    continue
+                }
+
+                if let Some(name) = db.name(entity, &self.locale) {
+                    matches.push((search::normalize::fold(&name), origin.clone(), entity));
+                }
+            }
+        }
+
+        matches.sort_by(|a, b| {
+            a.0.cmp(&b.0).then_with(|| self.origin_rank(&a.1).cmp(&self.origin_rank(&b.1)))
+        });
+
+        let matches = matches.into_iter().map(|(_, origin, entity)| (origin, entity)).collect();
+        SearchCursor::new(self.group(matches))
+    }
+
+    /// Every app which has a dated stable release, from the most recently released.
+    pub fn recently_released(&self) -> SearchCursor {
+        let mut matches: Vec<(i64, Rc<str>, Entity)> = Vec::new();
+
+        for (origin, db) in &self.origins {
             let origin: Rc<str> = Rc::from(origin.as_str());
-            origin_db.iter(|entity, name| {
-                if name.contains(package) {
-                    packages.push((origin.clone(), entity, name.to_owned()));
-                    return
+            for entity in db.entities() {
+                if !self.is_visible(db, entity) {
+                    continue
                 }
+
+                if let Some(timestamp) = db.latest_release(entity).and_then(|r| r.timestamp) {
+                    matches.push((timestamp, origin.clone(), entity));
+                }
+            }
+        }
+
+        matches.sort_by(|a, b| {
+            b.0.cmp(&a.0).then_with(|| self.origin_rank(&a.1).cmp(&self.origin_rank(&b.1)))
+        });
+
+        let matches = matches.into_iter().map(|(_, origin, entity)| (origin, entity)).collect();
+        SearchCursor::new(self.group(matches))
+    }
+
+    /// Every category, and the number of apps in each.
+    pub fn categories(&self) -> Vec<(String, usize)> {
+        let mut counts: BTreeMap<String, usize> = BTreeMap::new();
+
+        for categories in self.app_categories() {
+            for category in categories {
+                *counts.entry(category).or_insert(0) += 1;
+            }
+        }
+
+        counts.into_iter().collect()
+    }
+
+    /// The freedesktop main categories, with their subcategories beneath them.
+    pub fn category_hierarchy(&self) -> Vec<CategoryNode> {
+        let apps = self.app_categories();
+        categories::hierarchy(apps.iter().map(Vec::as_slice))
+    }
+
+    /// Categories of each app, taken from its most preferred origin.
+    fn app_categories(&self) -> Vec<Vec<String>> {
+        let mut apps: HashMap<String, (usize, Vec<String>)> = HashMap::new();
+
+        for (origin, db) in &self.origins {
+            let rank = self.origin_rank(origin);
+            for entity in db.entities().filter(|&entity| self.is_visible(db, entity)) {
+                let id = match db.id(entity) {
+                    Some(id) => id,
+                    None => continue
+                };
+
+                match apps.get(&id) {
+                    Some((existing, _)) if *existing <= rank => (),
+                    _ => {
+                        apps.insert(id, (rank, db.categories(entity)));
+                    }
+                }
+            }
+        }
+
+        apps.into_iter().map(|(_, (_, categories))| categories).collect()
+    }
+
+    /// Checks if a component passes the filters which apply to every search and browse result.
+    fn is_visible(&self, db: &OriginDb, entity: Entity) -> bool {
+        self.is_permitted(db, entity) && self.is_listed_kind(db, entity)
+    }
+
+    fn is_listed_kind(&self, db: &OriginDb, entity: Entity) -> bool {
+        match self.kinds.as_ref() {
+            Some(kinds) => db.kind(entity).is_some_and(|kind| kinds.contains(&kind)),
+            None => true
+        }
+    }
+
+    /// Checks if a component passes the license and age restrictions, which a query can not
+    /// override.
+    fn is_permitted(&self, db: &OriginDb, entity: Entity) -> bool {
+        if self.approved_licenses_only {
+            let approved = db.license_expression(entity).is_some_and(|l| l.is_approved());
+            if !approved {
+                return false;
+            }
+        }
+
+        if let Some(limit) = self.age_limit {
+            if db.content_rating(entity).is_some_and(|rating| rating.age() > limit) {
+                return false;
+            }
+        }
+
+        true
+    }
+
+    pub fn license(&self, origin: &str, entity: Entity) -> Option<License> {
+        self.origins.get(origin)?.license_expression(entity)
+    }
+
+    /// Links to show on the details page of a component.
+    pub fn urls(&self, origin: &str, entity: Entity) -> Vec<(UrlKind, String)> {
+        self.origins.get(origin).map_or_else(Vec::new, |db| db.urls(entity))
+    }
+
+    /// The `.desktop` file which launches a component, if it is installed.
+    pub fn desktop_file(&self, origin: &str, entity: Entity) -> Option<PathBuf> {
+        let db = self.origins.get(origin)?;
+        if !self.is_installed(db, entity) {
+            return None;
+        }
+
+        let dirs = launchable::data_dirs();
+        db.launchables(entity)
+            .iter()
+            .find_map(|desktop_id| launchable::find_desktop_file(desktop_id, &dirs))
+    }
+
+    pub fn screenshots(&self, origin: &str, entity: Entity) -> Vec<Screenshot> {
+        self.origins.get(origin).map_or_else(Vec::new, |db| db.screenshots(entity))
+    }
+
+    /// Apps which provide the given item, such as the `gimp` binary or the `image/webp` media type.
+    pub fn providers(&self, kind: ProvidedKind, value: &str) -> Vec<AppEntry> {
+        let mut matches: Vec<(Rc<str>, Entity)> = Vec::new();
+
+        for (origin, db) in &self.origins {
+            let origin: Rc<str> = Rc::from(origin.as_str());
+            for entity in db.provided_by(kind, value) {
+                matches.push((origin.clone(), entity));
+            }
+        }
+
+        self.merge(matches)
+    }
+
+    /// Packages which provide a command that was not found. If none provide it, packages
+    /// providing commands with similar names are suggested instead.
+    ///
+    /// Candidates are sorted by how closely their command matches, and then by origin preference.
+    pub fn command_not_found(&self, command: &str) -> Vec<CommandCandidate> {
+        let mut matches: Vec<(usize, usize, CommandCandidate)> = Vec::new();
+
+        let candidate = |db: &OriginDb, origin: &str, entity: Entity, provided: String| {
+            CommandCandidate {
+                command: provided,
+                origin: origin.to_owned(),
+                id: db.id(entity).unwrap_or_default(),
+                name: db.name(entity, &self.locale).unwrap_or_default(),
+                package: db.package(entity).unwrap_or_default(),
+                installed: self.is_installed(db, entity),
+                flatpak: flatpak::is_remote(origin),
+            }
+        };
+
+        for (origin, db) in &self.origins {
+            for entity in db.provided_by(ProvidedKind::Binary, command) {
+                let candidate = candidate(db, origin, entity, command.to_owned());
+                matches.push((0, self.origin_rank(origin), candidate));
+            }
+        }
+
+        if matches.is_empty() {
+            let max = search::fuzzy::max_distance(command);
+            for (origin, db) in &self.origins {
+                for (provided, entity) in db.provided(ProvidedKind::Binary) {
+                    if let Some(distance) = search::fuzzy::distance(command, &provided, max) {
+                        let candidate = candidate(db, origin, entity, provided);
+                        matches.push((distance, self.origin_rank(origin), candidate));
+                    }
+                }
+            }
+        }
+
+        matches.sort_by_key(|m| (m.0, m.1));
+
+        // Each package is suggested once for each command, from its most preferred origin
+        let mut candidates: Vec<CommandCandidate> = Vec::new();
+        for (_, _, candidate) in matches {
+            let duplicate = candidates.iter().any(|c| {
+                c.command == candidate.command
+                    && c.flatpak == candidate.flatpak
+                    && c.target() == candidate.target()
             });
+
+            if !duplicate {
+                candidates.push(candidate);
+            }
+        }
+
+        candidates
+    }
+
+    /// Apps whose modalias patterns match the modalias of a device, such as its driver.
+    pub fn modalias_providers(&self, modalias: &str) -> Vec<AppEntry> {
+        let mut matches: Vec<(Rc<str>, Entity)> = Vec::new();
+
+        for (origin, db) in &self.origins {
+            let origin: Rc<str> = Rc::from(origin.as_str());
+            for (pattern, entity) in db.provided(ProvidedKind::Modalias) {
+                if provides::glob_match(&pattern, modalias) {
+                    matches.push((origin.clone(), entity));
+                }
+            }
+        }
+
+        self.merge(matches)
+    }
+
+    /// Drivers and firmware whose modalias patterns match the devices of the system, for a
+    /// "recommended for your hardware" view.
+    pub fn hardware_recommendations(&self, hardware: &Hardware) -> Vec<AppEntry> {
+        let mut matches: Vec<(Rc<str>, Entity)> = Vec::new();
+
+        for (origin, db) in &self.origins {
+            let origin: Rc<str> = Rc::from(origin.as_str());
+            let mut entities: Vec<Entity> = db.provided(ProvidedKind::Modalias)
+                .into_iter()
+                .filter(|(pattern, _)| hardware.matches(pattern))
+                .map(|(_, entity)| entity)
+                .filter(|&entity| {
+                    let kind = db.kind(entity);
+                    kind == Some(ComponentKind::Driver) || kind == Some(ComponentKind::Firmware)
+                })
+                .collect();
+
+            entities.sort_unstable();
+            entities.dedup();
+            matches.extend(entities.into_iter().map(|entity| (origin.clone(), entity)));
         }
 
-        packages
+        self.merge(matches)
+    }
+
+    /// Addons which extend the component with the given ID from any origin, along with whether
+    /// each is installed, sorted by name.
+    pub fn addons(&self, id: &str) -> Vec<(AppEntry, bool)> {
+        let mut matches: Vec<(Rc<str>, Entity)> = Vec::new();
+
+        for (origin, db) in &self.origins {
+            let origin: Rc<str> = Rc::from(origin.as_str());
+            for entity in db.addons_of(id) {
+                if self.is_permitted(db, entity) {
+                    matches.push((origin.clone(), entity));
+                }
+            }
+        }
+
+        let mut addons: Vec<(AppEntry, bool)> = self.merge(matches)
+            .into_iter()
+            .map(|addon| {
+                let installed = addon.variants.iter().any(|(origin, entity)| {
+                    self.origins.get(&**origin).is_some_and(|db| self.is_installed(db, *entity))
+                });
+
+                (addon, installed)
+            })
+            .collect();
+
+        addons.sort_by_cached_key(|(addon, _)| search::normalize::fold(&addon.name));
+        addons
+    }
+
+    /// Components that an addon extends, from any origin.
+    pub fn extended_components(&self, origin: &str, entity: Entity) -> Vec<AppEntry> {
+        let extends = match self.origins.get(origin) {
+            Some(db) => db.extends(entity),
+            None => return Vec::new()
+        };
+
+        let mut matches: Vec<(Rc<str>, Entity)> = Vec::new();
+        for id in &extends {
+            for (origin, db) in &self.origins {
+                if let Some(entity) = db.entity(id) {
+                    matches.push((Rc::from(origin.as_str()), entity));
+                }
+            }
+        }
+
+        self.merge(matches)
+    }
+
+    pub fn developer(&self, origin: &str, entity: Entity) -> Option<Developer> {
+        self.origins.get(origin)?.developer(entity)
+    }
+
+    pub fn project_group(&self, origin: &str, entity: Entity) -> Option<String> {
+        self.origins.get(origin)?.project_group(entity)
+    }
+
+    pub fn content_rating(&self, origin: &str, entity: Entity) -> Option<ContentRating> {
+        self.origins.get(origin)?.content_rating(entity)
+    }
+
+    /// Releases of a component, from newest to oldest, for a "What's new" section.
+    pub fn releases(&self, origin: &str, entity: Entity) -> Vec<Release> {
+        self.origins.get(origin).map_or_else(Vec::new, |db| db.releases(entity))
+    }
+
+    /// The description of a component in the user's language, parsed into rich text.
+    pub fn description(&self, origin: &str, entity: Entity) -> Option<Description> {
+        let markup = self.origins.get(origin)?.description(entity, &self.locale)?;
+        Some(Description::parse(&markup))
+    }
+
+    /// Checks if the component is installed, either through its package or as a flatpak.
+    pub fn is_installed(&self, db: &OriginDb, entity: Entity) -> bool {
+        let package = db.package(entity).unwrap_or_default();
+        let id = db.id(entity).unwrap_or_default();
+        self.installed.contains(&package, &id)
     }
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    pub(crate) fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir()
+            .join(format!("appstream-cache-{}-{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    pub(crate) fn component(id: &str, name: &str, categories: &[&str]) -> Dep11Package {
+        Dep11Package {
+            type_: "desktop-application".into(),
+            id: id.into(),
+            package: id.into(),
+            name: std::iter::once(("C".to_owned(), name.to_owned())).collect(),
+            categories: Some(categories.iter().map(|&c| c.to_owned()).collect()),
+            ..Dep11Package::default()
+        }
+    }
+
+    fn refresh(db: &mut Database, origin: &str, components: Vec<Dep11Package>) {
+        db.origins.remove(origin);
+        let locale = db.locale.clone();
+        let origin = db.get_origin(origin);
+        for component in components {
+            origin.add_dep11_package(component, &locale);
+        }
+    }
+
+    fn names(db: &Database, mut cursor: SearchCursor) -> Vec<String> {
+        let mut names: Vec<String> = cursor.next_page(db, 100)
+            .into_iter()
+            .map(|entry| entry.name)
+            .collect();
+
+        names.sort();
+        names
+    }
+
+    #[test]
+    fn refresh_replaces_origin() {
+        let path = temp_dir("refresh");
+        let mut db = Database::new(path.clone(), Locale::new("C"));
+
+        refresh(&mut db, "o", vec![
+            component("alpha", "Alpha Game", &["Game"]),
+            component("beta", "Beta Editor", &["Development"]),
+        ]);
+
+        refresh(&mut db, "o", vec![
+            component("beta", "Beta Editor", &["Development"]),
+            component("gamma", "Gamma Tool", &["Utility"]),
+        ]);
+
+        assert!(names(&db, db.browse("Game")).is_empty());
+        assert_eq!(names(&db, db.browse("Utility")), ["Gamma Tool"]);
+        assert!(names(&db, smol::block_on(db.search_verbatim("alpha"))).is_empty());
+        assert_eq!(names(&db, smol::block_on(db.search("beta"))), ["Beta Editor"]);
+        assert_eq!(smol::block_on(db.search_for("gamma")).len(), 1);
+
+        let origin = &db.origins["o"];
+        assert_eq!(origin.search.complete_term("beta", 1), [("beta".to_owned(), 1)]);
+        assert!(origin.entities_named("Alpha Game").is_empty());
+        assert_eq!(origin.entities_named("Beta Editor"), [0]);
+
+        let mut named = Vec::new();
+        origin.iter(|entity, name| named.push((entity, name.to_owned())));
+        named.sort();
+        assert_eq!(named, [(0, "Beta Editor".to_owned()), (1, "Gamma Tool".to_owned())]);
+
+        drop(db);
+        let _ = std::fs::remove_dir_all(path);
+    }
+
+    #[test]
+    fn completions_skip_hidden_kinds() {
+        let path = temp_dir("complete");
+        let mut db = Database::new(path.clone(), Locale::new("C"));
+        db.set_kinds(Some(ComponentKind::APPS.to_vec()));
+
+        let mut components: Vec<Dep11Package> = (0..8)
+            .map(|n| Dep11Package {
+                type_: "font".into(),
+                ..component(&format!("font{}", n), &format!("Noto Font {}", n), &[])
+            })
+            .collect();
+
+        components.push(component("viewer", "Noto Viewer", &[]));
+        refresh(&mut db, "o", components);
+
+        assert_eq!(db.complete("noto", 8), ["Noto Viewer"]);
+        assert_eq!(db.complete("Noto V", 8), ["Noto Viewer"]);
+        assert_eq!(db.complete("not", 1), ["Noto Viewer"]);
+        assert!(db.complete("noto ", 8).contains(&"Noto Viewer".to_owned()));
+
+        drop(db);
+        let _ = std::fs::remove_dir_all(path);
+    }
 
+    #[test]
+    fn exclusions_match_whole_terms() {
+        let path = temp_dir("exclude");
+        let mut db = Database::new(path.clone(), Locale::new("C"));
 
+        refresh(&mut db, "o", vec![
+            component("gnome-disks", "GNOME Disks", &["Utility"]),
+            component("gnomebaker", "GnomeBaker Disks", &["Utility"]),
+            component("kdisks", "KDE Disks", &["Utility"]),
+        ]);
+
+        let results = |query: &str| names(&db, smol::block_on(db.search(query)));
+        assert_eq!(results("disks -gnome"), ["GnomeBaker Disks", "KDE Disks"]);
+        assert_eq!(results("disks -gnomebaker -kde"), ["GNOME Disks"]);
+        assert_eq!(results("disks - category:Utility"), results("disks"));
+
+        drop(db);
+        let _ = std::fs::remove_dir_all(path);
+    }
+
+    #[test]
+    fn matching_ignores_case_and_diacritics() {
+        let path = temp_dir("normalize");
+        let mut db = Database::new(path.clone(), Locale::new("C"));
+
+        refresh(&mut db, "o", vec![
+            component("firefox", "Firefox", &[]),
+            component("cafe", "Café Manager", &[]),
+            component("strasse", "Straße Planner", &[]),
+            component("telegram", "Телеграм", &[]),
+        ]);
+
+        let results = |query: &str| names(&db, smol::block_on(db.search_verbatim(query)));
+        assert_eq!(results("firefox"), ["Firefox"]);
+        assert_eq!(results("FIREFOX"), ["Firefox"]);
+        assert_eq!(results("cafe"), ["Café Manager"]);
+        assert_eq!(results("CAFÉ"), ["Café Manager"]);
+        assert_eq!(results("strasse"), ["Straße Planner"]);
+        assert_eq!(results("telegram"), ["Телеграм"]);
+        assert_eq!(results("телеграм"), ["Телеграм"]);
+
+        drop(db);
+        let _ = std::fs::remove_dir_all(path);
+    }
+
+    #[test]
+    fn latest_release_is_the_newest_stable() {
+        use crate::dep11::appstream::Dep11Release;
+
+        let release = |version: &str, type_: &str, timestamp: Option<i64>| Dep11Release {
+            version: version.into(),
+            type_: Some(type_.into()),
+            unix_timestamp: timestamp,
+            ..Dep11Release::default()
+        };
+
+        let path = temp_dir("releases");
+        let mut db = Database::new(path.clone(), Locale::new("C"));
+
+        refresh(&mut db, "o", vec![
+            Dep11Package {
+                releases: Some(vec![
+                    release("1.0", "stable", Some(100)),
+                    release("nightly", "development", None),
+                    release("2.1-beta", "development", Some(300)),
+                    release("2.0", "stable", Some(200)),
+                ]),
+                ..component("app", "App", &[])
+            },
+            component("none", "No Releases", &[]),
+        ]);
+
+        let origin = &db.origins["o"];
+        let versions: Vec<String> = origin.releases(0).into_iter().map(|r| r.version).collect();
+        assert_eq!(versions, ["2.1-beta", "2.0", "1.0", "nightly"]);
+        assert_eq!(origin.latest_release(0).map(|r| r.version).as_deref(), Some("2.0"));
+        assert_eq!(origin.latest_release(1), None);
+
+        drop(db);
+        let _ = std::fs::remove_dir_all(path);
+    }
+
+    #[test]
+    fn commands_not_found() {
+        let providing = |id: &str, package: &str, binaries: &[&str]| Dep11Package {
+            package: package.into(),
+            provides: Some(dep11::appstream::Provides {
+                binaries: Some(binaries.iter().map(|&b| b.to_owned()).collect()),
+                ..Default::default()
+            }),
+            ..component(id, id, &[])
+        };
+
+        let path = temp_dir("commands");
+        let mut db = Database::new(path.clone(), Locale::new("C"));
+        db.set_origin_preference(vec!["main".into()]);
+
+        refresh(&mut db, "backports", vec![
+            providing("org.gimp.GIMP", "gimp", &["gimp", "gimp-2.10"]),
+        ]);
+
+        refresh(&mut db, "main", vec![
+            providing("org.gimp.GIMP", "gimp", &["gimp"]),
+            providing("gimp-console", "gimp-console", &["gimp"]),
+            providing("org.gnome.Geary", "geary", &["geary"]),
+        ]);
+
+        let candidates = db.command_not_found("gimp");
+        let found: Vec<(&str, &str, &str)> = candidates.iter()
+            .map(|c| (c.command.as_str(), c.package.as_str(), c.origin.as_str()))
+            .collect();
+
+        // Exact matches only, with each package once from its preferred origin
+        assert_eq!(found, [("gimp", "gimp", "main"), ("gimp", "gimp-console", "main")]);
+        assert_eq!(candidates[0].install_hint(), "sudo apt install gimp");
+        assert!(!candidates[0].installed);
+
+        // Similar commands are suggested when nothing provides the command itself
+        let similar: Vec<String> = db.command_not_found("gaery")
+            .into_iter()
+            .map(|c| c.command)
+            .collect();
+        assert_eq!(similar, ["geary"]);
+
+        let similar: Vec<String> = db.command_not_found("gimp-2.1")
+            .into_iter()
+            .map(|c| c.command)
+            .collect();
+        assert_eq!(similar, ["gimp-2.10"]);
+
+        let similar: Vec<(String, String)> = db.command_not_found("gimq")
+            .into_iter()
+            .map(|c| (c.package, c.origin))
+            .collect();
+        assert_eq!(similar, [
+            ("gimp".to_owned(), "main".to_owned()),
+            ("gimp-console".to_owned(), "main".to_owned()),
+        ]);
+
+        assert!(db.command_not_found("inkscape").is_empty());
+
+        drop(db);
+        let _ = std::fs::remove_dir_all(path);
+    }
+}
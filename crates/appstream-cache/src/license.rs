@@ -0,0 +1,334 @@
+//! Parsing of SPDX license expressions, and their classification as free software.
+//!
+//! Expressions combine license identifiers with the `AND`, `OR`, and `WITH` operators, where
+//! `WITH` binds tightest and `OR` loosest, and parentheses may override that precedence.
+
+/// Licenses known to this module, and whether each is approved by the FSF and the OSI.
+/// Identifiers are listed without their `-only` or `-or-later` suffixes.
+const LICENSES: &[(&str, bool, bool)] = &[
+    ("0BSD", false, true),
+    ("AFL-3.0", true, true),
+    ("AGPL-3.0", true, true),
+    ("Apache-1.1", true, true),
+    ("Apache-2.0", true, true),
+    ("APSL-2.0", true, true),
+    ("Artistic-2.0", true, true),
+    ("BSD-2-Clause", true, true),
+    ("BSD-3-Clause", true, true),
+    ("BSL-1.0", true, true),
+    ("CC-BY-3.0", false, false),
+    ("CC-BY-4.0", true, false),
+    ("CC-BY-NC-3.0", false, false),
+    ("CC-BY-NC-4.0", false, false),
+    ("CC-BY-NC-ND-4.0", false, false),
+    ("CC-BY-NC-SA-4.0", false, false),
+    ("CC-BY-ND-4.0", false, false),
+    ("CC-BY-SA-3.0", false, false),
+    ("CC-BY-SA-4.0", true, false),
+    ("CC0-1.0", true, false),
+    ("CDDL-1.0", true, true),
+    ("CECILL-2.0", true, false),
+    ("CPL-1.0", true, true),
+    ("ECL-2.0", true, true),
+    ("EPL-1.0", true, true),
+    ("EPL-2.0", true, true),
+    ("EUPL-1.1", true, true),
+    ("EUPL-1.2", true, true),
+    ("FTL", true, false),
+    ("GFDL-1.1", true, false),
+    ("GFDL-1.2", true, false),
+    ("GFDL-1.3", true, false),
+    ("GPL-1.0", false, false),
+    ("GPL-2.0", true, true),
+    ("GPL-3.0", true, true),
+    ("IJG", true, false),
+    ("IPL-1.0", true, true),
+    ("ISC", true, true),
+    ("LGPL-2.0", true, true),
+    ("LGPL-2.1", true, true),
+    ("LGPL-3.0", true, true),
+    ("LPPL-1.3c", true, true),
+    ("MIT", true, true),
+    ("MIT-0", false, true),
+    ("MPL-1.1", true, true),
+    ("MPL-2.0", true, true),
+    ("MS-PL", true, true),
+    ("MS-RL", true, true),
+    ("NCSA", true, true),
+    ("OFL-1.1", true, true),
+    ("OpenSSL", true, false),
+    ("PHP-3.01", false, true),
+    ("PSF-2.0", false, true),
+    ("Python-2.0", true, true),
+    ("QPL-1.0", true, true),
+    ("RPSL-1.0", true, true),
+    ("Sleepycat", true, true),
+    ("Unicode-DFS-2016", false, true),
+    ("Unlicense", true, true),
+    ("Vim", true, false),
+    ("W3C", true, true),
+    ("WTFPL", true, false),
+    ("X11", true, false),
+    ("Zlib", true, true),
+    ("ZPL-2.0", true, true),
+    ("ZPL-2.1", true, true),
+];
+
+/// Reference to a proprietary license, which may be followed by `=` and the URL of its terms.
+const PROPRIETARY: &str = "LicenseRef-proprietary";
+
+/// References to licenses outside of the SPDX list which AppStream considers free.
+const FREE_REFERENCES: &[&str] = &["LicenseRef-free", "LicenseRef-public-domain"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Classification {
+    /// Approved as free software by the FSF.
+    Free,
+    /// Approved as open source by the OSI, but not by the FSF.
+    OpenSource,
+    /// Neither approved by the FSF nor the OSI.
+    Proprietary,
+    /// Refers to licenses which are not known to this module.
+    Unknown,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum License {
+    /// A license identifier, where `or_later` is set by a `+` or `-or-later` suffix.
+    Id { id: String, or_later: bool },
+    With { license: Box<License>, exception: String },
+    And(Box<License>, Box<License>),
+    Or(Box<License>, Box<License>),
+}
+
+impl License {
+    /// Parses an SPDX license expression, such as `GPL-3.0+ AND (MIT OR Apache-2.0)`.
+    pub fn parse(expression: &str) -> Option<Self> {
+        let tokens = tokenize(expression);
+        let mut parser = Parser { tokens: &tokens, position: 0 };
+        let license = parser.or()?;
+
+        if parser.position == tokens.len() {
+            Some(license)
+        } else {
+            None
+        }
+    }
+
+    pub fn classify(&self) -> Classification {
+        let (fsf, osi) = self.approval();
+
+        if fsf {
+            Classification::Free
+        } else if osi {
+            Classification::OpenSource
+        } else if self.is_known() {
+            Classification::Proprietary
+        } else {
+            Classification::Unknown
+        }
+    }
+
+    /// Checks if the licensing is approved by either the FSF or the OSI.
+    pub fn is_approved(&self) -> bool {
+        let (fsf, osi) = self.approval();
+        fsf || osi
+    }
+
+    /// Whether the FSF and the OSI approve of the licensing. An `OR` expression is approved if
+    /// either side is, since the user may choose between them, and an `AND` only if both are.
+    pub fn approval(&self) -> (bool, bool) {
+        match self {
+            License::Id { id, .. } => lookup(id).map_or_else(
+                || {
+                    let free = FREE_REFERENCES.iter().any(|r| id.starts_with(r));
+                    (free, false)
+                },
+                |(_, fsf, osi)| (fsf, osi)
+            ),
+            License::With { license, .. } => license.approval(),
+            License::And(a, b) => {
+                let (a, b) = (a.approval(), b.approval());
+                (a.0 && b.0, a.1 && b.1)
+            }
+            License::Or(a, b) => {
+                let (a, b) = (a.approval(), b.approval());
+                (a.0 || b.0, a.1 || b.1)
+            }
+        }
+    }
+
+    /// Checks if every license in the expression is known to this module.
+    pub fn is_known(&self) -> bool {
+        match self {
+            License::Id { id, .. } => {
+                id.starts_with(PROPRIETARY)
+                    || FREE_REFERENCES.iter().any(|r| id.starts_with(r))
+                    || lookup(id).is_some()
+            }
+            License::With { license, .. } => license.is_known(),
+            License::And(a, b) | License::Or(a, b) => a.is_known() && b.is_known(),
+        }
+    }
+
+    /// Checks if the expression refers to a license, where a query of `GPL-3.0+` only matches
+    /// licenses permitting later versions, but `GPL-3.0` matches either.
+    pub fn mentions(&self, query: &str) -> bool {
+        match self {
+            License::Id { id, or_later } => {
+                let (query, query_or_later) = split_suffix(query);
+                id.eq_ignore_ascii_case(&query) && (*or_later || !query_or_later)
+            }
+            License::With { license, .. } => license.mentions(query),
+            License::And(a, b) | License::Or(a, b) => a.mentions(query) || b.mentions(query),
+        }
+    }
+}
+
+fn lookup(id: &str) -> Option<(&'static str, bool, bool)> {
+    LICENSES.iter().copied().find(|(known, _, _)| known.eq_ignore_ascii_case(id))
+}
+
+/// Separates an identifier from a suffix which permits later versions of the license.
+fn split_suffix(id: &str) -> (String, bool) {
+    if let Some(id) = id.strip_suffix('+') {
+        (id.to_owned(), true)
+    } else if let Some(id) = id.strip_suffix("-or-later") {
+        (id.to_owned(), true)
+    } else if let Some(id) = id.strip_suffix("-only") {
+        (id.to_owned(), false)
+    } else {
+        (id.to_owned(), false)
+    }
+}
+
+fn tokenize(expression: &str) -> Vec<String> {
+    expression.replace('(', " ( ")
+        .replace(')', " ) ")
+        .split_whitespace()
+        .map(String::from)
+        .collect()
+}
+
+struct Parser<'a> {
+    tokens: &'a [String],
+    position: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn peek_operator(&self, operator: &str) -> bool {
+        self.tokens.get(self.position).is_some_and(|t| t.eq_ignore_ascii_case(operator))
+    }
+
+    fn or(&mut self) -> Option<License> {
+        let mut license = self.and()?;
+
+        while self.peek_operator("OR") {
+            self.position += 1;
+            license = License::Or(Box::new(license), Box::new(self.and()?));
+        }
+
+        Some(license)
+    }
+
+    fn and(&mut self) -> Option<License> {
+        let mut license = self.with()?;
+
+        while self.peek_operator("AND") {
+            self.position += 1;
+            license = License::And(Box::new(license), Box::new(self.with()?));
+        }
+
+        Some(license)
+    }
+
+    fn with(&mut self) -> Option<License> {
+        let license = self.primary()?;
+
+        if self.peek_operator("WITH") {
+            let exception = self.tokens.get(self.position + 1)?.clone();
+            self.position += 2;
+            return Some(License::With { license: Box::new(license), exception });
+        }
+
+        Some(license)
+    }
+
+    fn primary(&mut self) -> Option<License> {
+        let token = self.tokens.get(self.position)?;
+        self.position += 1;
+
+        match token.as_str() {
+            "(" => {
+                let license = self.or()?;
+                if self.tokens.get(self.position).map(String::as_str) != Some(")") {
+                    return None;
+                }
+
+                self.position += 1;
+                Some(license)
+            }
+            ")" => None,
+            _ if ["AND", "OR", "WITH"].iter().any(|op| token.eq_ignore_ascii_case(op)) => None,
+            id => {
+                let (id, or_later) = split_suffix(id);
+                Some(License::Id { id, or_later })
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn classify(expression: &str) -> Classification {
+        License::parse(expression).unwrap().classify()
+    }
+
+    #[test]
+    fn precedence() {
+        let id = |id: &str| Box::new(License::Id { id: id.into(), or_later: false });
+
+        assert_eq!(
+            License::parse("MIT OR Apache-2.0 AND Zlib"),
+            Some(License::Or(id("MIT"), Box::new(License::And(id("Apache-2.0"), id("Zlib")))))
+        );
+
+        assert_eq!(
+            License::parse("(MIT OR Apache-2.0) AND Zlib"),
+            Some(License::And(Box::new(License::Or(id("MIT"), id("Apache-2.0"))), id("Zlib")))
+        );
+
+        assert_eq!(License::parse("MIT AND"), None);
+        assert_eq!(License::parse("(MIT"), None);
+    }
+
+    #[test]
+    fn classification() {
+        assert_eq!(classify("GPL-3.0-or-later"), Classification::Free);
+        assert_eq!(classify("MIT-0"), Classification::OpenSource);
+        assert_eq!(classify("CC-BY-NC-4.0"), Classification::Proprietary);
+        assert_eq!(classify("LicenseRef-free=https://example.com/terms"), Classification::Free);
+        assert_eq!(classify("MIT AND CC-BY-NC-4.0"), Classification::Proprietary);
+        assert_eq!(classify("MIT OR CC-BY-NC-4.0"), Classification::Free);
+        assert_eq!(classify("Made-Up-1.0"), Classification::Unknown);
+    }
+
+    #[test]
+    fn proprietary_with_url() {
+        assert_eq!(classify("LicenseRef-proprietary"), Classification::Proprietary);
+        assert_eq!(
+            classify("LicenseRef-proprietary=https://example.com/eula"),
+            Classification::Proprietary
+        );
+    }
+
+    #[test]
+    fn mentions() {
+        let license = License::parse("GPL-3.0+ WITH GCC-exception-3.1").unwrap();
+        assert!(license.mentions("GPL-3.0"));
+        assert!(license.mentions("gpl-3.0-or-later"));
+        assert!(!License::parse("GPL-3.0-only").unwrap().mentions("GPL-3.0+"));
+    }
+}
@@ -0,0 +1,193 @@
+//! Selection of translations through a chain of fallback languages.
+
+use std::collections::HashMap;
+use std::env;
+
+/// Translations of a string, keyed by normalized language tag.
+pub type Translations = HashMap<String, String>;
+
+/// The untranslated language that every chain falls back to.
+pub const UNTRANSLATED: &str = "C";
+
+/// An ordered list of languages that translations are looked up in.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Locale {
+    chain: Vec<String>,
+}
+
+impl Locale {
+    /// Builds a chain from one or more colon-separated languages, such as `pt_BR:de`.
+    ///
+    /// Each language is followed by its less specific forms, so `pt_BR.UTF-8` becomes
+    /// `pt_BR` → `pt`, and the chain always ends with `C`.
+    pub fn new(languages: &str) -> Self {
+        let mut chain: Vec<String> = Vec::new();
+
+        for language in languages.split(':').filter(|l| !l.is_empty()) {
+            for variant in variants(&normalize(language)) {
+                if !chain.contains(&variant) {
+                    chain.push(variant);
+                }
+            }
+        }
+
+        chain.retain(|language| {
+            !language.is_empty() && language != UNTRANSLATED && language != "posix"
+        });
+        chain.push(UNTRANSLATED.to_owned());
+
+        Self { chain }
+    }
+
+    /// Derives the chain from `LANGUAGE`, `LC_ALL`, `LC_MESSAGES`, or `LANG`.
+    ///
+    /// As with gettext, `LANGUAGE` is ignored when the locale is set to `C`.
+    pub fn from_env() -> Self {
+        let locale: String = ["LC_ALL", "LC_MESSAGES", "LANG"].iter()
+            .filter_map(|var| env::var(var).ok())
+            .find(|value| !value.is_empty())
+            .unwrap_or_default();
+
+        let language = normalize(&locale);
+        if !language.is_empty() && language != UNTRANSLATED && language != "posix" {
+            if let Ok(languages) = env::var("LANGUAGE") {
+                if !languages.is_empty() {
+                    return Self::new(&languages);
+                }
+            }
+        }
+
+        Self::new(&locale)
+    }
+
+    /// Languages in the order that they are preferred.
+    pub fn chain(&self) -> &[String] {
+        &self.chain
+    }
+
+    /// The most preferred language.
+    pub fn language(&self) -> &str {
+        &self.chain[0]
+    }
+
+    /// Selects the translation of the most preferred language which has one.
+    pub fn resolve<'a, T>(&self, translations: &'a HashMap<String, T>) -> Option<&'a T> {
+        self.chain.iter().find_map(|language| translations.get(language))
+    }
+}
+
+/// Normalizes a language tag, so that `en-gb.UTF-8` and `en_GB` are equivalent.
+pub fn normalize(tag: &str) -> String {
+    let (tag, modifier) = match tag.find('@') {
+        Some(pos) => (&tag[..pos], Some(&tag[pos..])),
+        None => (tag, None)
+    };
+
+    let tag = tag.split('.').next().unwrap_or(tag);
+
+    let mut parts = tag.split(['_', '-']);
+    let mut normalized = parts.next().unwrap_or("").to_owned();
+
+    if normalized != UNTRANSLATED {
+        normalized.make_ascii_lowercase();
+    }
+
+    for part in parts.filter(|p| !p.is_empty()) {
+        normalized.push('_');
+        match part.len() {
+            // Regions, such as `BR`
+            2 => normalized.push_str(&part.to_ascii_uppercase()),
+            // Scripts, such as `Latn`
+            4 => {
+                let mut chars = part.chars();
+                normalized.extend(chars.next().map(|c| c.to_ascii_uppercase()));
+                normalized.push_str(&chars.as_str().to_ascii_lowercase());
+            }
+            _ => normalized.push_str(part)
+        }
+    }
+
+    if let Some(modifier) = modifier {
+        normalized.push_str(modifier);
+    }
+
+    normalized
+}
+
+/// Normalizes the language tags of translations parsed from appstream data.
+pub fn normalize_keys<T>(translations: HashMap<String, T>) -> HashMap<String, T> {
+    translations.into_iter()
+        .map(|(language, text)| (normalize(&language), text))
+        .collect()
+}
+
+/// A normalized language followed by its less specific forms, such as `sr_RS@latin`,
+/// `sr@latin`, `sr_RS`, and `sr`.
+fn variants(language: &str) -> Vec<String> {
+    let (base, modifier) = match language.find('@') {
+        Some(pos) => (&language[..pos], Some(&language[pos..])),
+        None => (language, None)
+    };
+
+    let mut bases = vec![base.to_owned()];
+    let mut base = base;
+    while let Some(pos) = base.rfind('_') {
+        base = &base[..pos];
+        bases.push(base.to_owned());
+    }
+
+    let mut variants = Vec::new();
+
+    if let Some(modifier) = modifier {
+        variants.extend(bases.iter().map(|base| [base, modifier].concat()));
+    }
+
+    variants.extend(bases);
+    variants
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn normalize_tags() {
+        assert_eq!(normalize("en-gb.UTF-8"), "en_GB");
+        assert_eq!(normalize("pt_BR"), "pt_BR");
+        assert_eq!(normalize("zh-hant-tw"), "zh_Hant_TW");
+        assert_eq!(normalize("sr_RS.UTF-8@latin"), "sr_RS@latin");
+        assert_eq!(normalize("C.UTF-8"), "C");
+        assert_eq!(normalize("DE"), "de");
+    }
+
+    #[test]
+    fn chain_falls_back_to_less_specific_languages() {
+        assert_eq!(Locale::new("pt_BR.UTF-8").chain(), ["pt_BR", "pt", "C"]);
+        assert_eq!(
+            Locale::new("sr_RS.UTF-8@latin").chain(),
+            ["sr_RS@latin", "sr@latin", "sr_RS", "sr", "C"]
+        );
+    }
+
+    #[test]
+    fn chain_combines_languages_without_duplicates() {
+        assert_eq!(Locale::new("de_AT:de:en_US").chain(), ["de_AT", "de", "en_US", "en", "C"]);
+        assert_eq!(Locale::new("C:POSIX").chain(), ["C"]);
+        assert_eq!(Locale::new("").chain(), ["C"]);
+    }
+
+    #[test]
+    fn resolve_prefers_the_first_language_with_a_translation() {
+        let mut translations = Translations::new();
+        translations.insert("C".into(), "Color".into());
+        translations.insert("sr@latin".into(), "Boja".into());
+        translations.insert("sr".into(), "Боја".into());
+
+        let locale = Locale::new("sr_RS.UTF-8@latin");
+        assert_eq!(locale.resolve(&translations).map(String::as_str), Some("Boja"));
+        assert_eq!(locale.language(), "sr_RS@latin");
+
+        let locale = Locale::new("fr_FR");
+        assert_eq!(locale.resolve(&translations).map(String::as_str), Some("Color"));
+    }
+}
@@ -0,0 +1,337 @@
+//! Parsing of AppStream's description markup into rich text.
+//!
+//! Descriptions are restricted to paragraphs (`<p>`), lists (`<ul>` and `<ol>`) of items
+//! (`<li>`), and the inline `<em>` and `<code>` elements. Unknown elements are ignored, but
+//! their text is kept, and the items of nested lists are flattened into the outermost list.
+
+use std::fmt::Write;
+
+/// A run of text sharing the same inline style.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Span {
+    pub text: String,
+    pub emphasis: bool,
+    pub code: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Block {
+    Paragraph(Vec<Span>),
+    List { ordered: bool, items: Vec<Vec<Span>> },
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Description {
+    pub blocks: Vec<Block>,
+}
+
+impl Description {
+    pub fn parse(markup: &str) -> Self {
+        let mut parser = Parser::default();
+        let mut rest = markup;
+
+        while !rest.is_empty() {
+            if rest.starts_with('<') {
+                let end = match rest.find('>') {
+                    Some(end) => end,
+                    None => break
+                };
+
+                parser.tag(&rest[1..end]);
+                rest = &rest[end + 1..];
+            } else {
+                let end = rest.find('<').unwrap_or(rest.len());
+                parser.text(&unescape(&rest[..end]));
+                rest = &rest[end..];
+            }
+        }
+
+        parser.finish()
+    }
+
+    /// Renders the description as Pango markup, for display in a label.
+    pub fn to_pango(&self) -> String {
+        let mut pango = String::new();
+
+        for block in &self.blocks {
+            if !pango.is_empty() {
+                pango.push_str("\n\n");
+            }
+
+            match block {
+                Block::Paragraph(spans) => write_pango_spans(&mut pango, spans),
+                Block::List { ordered, items } => {
+                    for (number, item) in items.iter().enumerate() {
+                        if number != 0 {
+                            pango.push('\n');
+                        }
+
+                        if *ordered {
+                            let _ = write!(pango, "{}. ", number + 1);
+                        } else {
+                            pango.push_str("• ");
+                        }
+
+                        write_pango_spans(&mut pango, item);
+                    }
+                }
+            }
+        }
+
+        pango
+    }
+
+    /// The text of the description without any styling, with a line for each paragraph and
+    /// list item.
+    pub fn to_plain_text(&self) -> String {
+        let mut text = String::new();
+
+        let mut push_line = |spans: &[Span]| {
+            if !text.is_empty() {
+                text.push('\n');
+            }
+
+            for span in spans {
+                text.push_str(&span.text);
+            }
+        };
+
+        for block in &self.blocks {
+            match block {
+                Block::Paragraph(spans) => push_line(spans),
+                Block::List { items, .. } => items.iter().for_each(|item| push_line(item)),
+            }
+        }
+
+        text
+    }
+}
+
+#[derive(Default)]
+struct Parser {
+    blocks: Vec<Block>,
+    spans: Vec<Span>,
+    items: Vec<Vec<Span>>,
+    /// Whether the current list is ordered, if a list is open.
+    list: Option<bool>,
+    /// Number of lists open within each other.
+    lists: u32,
+    emphasis: u32,
+    code: u32,
+}
+
+impl Parser {
+    fn tag(&mut self, tag: &str) {
+        let (closing, tag) = match tag.strip_prefix('/') {
+            Some(tag) => (true, tag),
+            None => (false, tag)
+        };
+
+        let name = tag.split_whitespace().next().unwrap_or("").trim_end_matches('/');
+
+        match name {
+            "p" | "li" => self.flush_spans(),
+            "ul" | "ol" => {
+                self.flush_spans();
+                if closing {
+                    self.lists = self.lists.saturating_sub(1);
+                    if self.lists == 0 {
+                        self.flush_list();
+                    }
+                } else {
+                    if self.lists == 0 {
+                        self.list = Some(name == "ol");
+                    }
+                    self.lists += 1;
+                }
+            }
+            "em" => nest(&mut self.emphasis, closing),
+            "code" => nest(&mut self.code, closing),
+            _ => ()
+        }
+    }
+
+    fn text(&mut self, text: &str) {
+        let emphasis = self.emphasis != 0;
+        let code = self.code != 0;
+
+        let mut collapsed = String::with_capacity(text.len());
+        let mut whitespace = false;
+        for character in text.chars() {
+            if character.is_whitespace() {
+                whitespace = true;
+            } else {
+                if whitespace {
+                    collapsed.push(' ');
+                    whitespace = false;
+                }
+                collapsed.push(character);
+            }
+        }
+
+        if whitespace {
+            collapsed.push(' ');
+        }
+
+        match self.spans.last_mut() {
+            Some(last) if last.emphasis == emphasis && last.code == code => {
+                last.text.push_str(&collapsed);
+            }
+            _ => self.spans.push(Span { text: collapsed, emphasis, code })
+        }
+    }
+
+    /// Takes the spans collected so far, trimming the whitespace that surrounds them.
+    fn take_spans(&mut self) -> Option<Vec<Span>> {
+        let mut spans = std::mem::take(&mut self.spans);
+
+        if let Some(first) = spans.first_mut() {
+            first.text = first.text.trim_start().to_owned();
+        }
+
+        if let Some(last) = spans.last_mut() {
+            last.text = last.text.trim_end().to_owned();
+        }
+
+        spans.retain(|span| !span.text.is_empty());
+
+        if spans.is_empty() {
+            None
+        } else {
+            Some(spans)
+        }
+    }
+
+    /// Ends the current paragraph, or list item if a list is open.
+    fn flush_spans(&mut self) {
+        if let Some(spans) = self.take_spans() {
+            if self.list.is_some() {
+                self.items.push(spans);
+            } else {
+                self.blocks.push(Block::Paragraph(spans));
+            }
+        }
+    }
+
+    fn flush_list(&mut self) {
+        if let Some(ordered) = self.list.take() {
+            let items = std::mem::take(&mut self.items);
+            if !items.is_empty() {
+                self.blocks.push(Block::List { ordered, items });
+            }
+        }
+    }
+
+    fn finish(mut self) -> Description {
+        self.flush_spans();
+        self.flush_list();
+        Description { blocks: self.blocks }
+    }
+}
+
+fn nest(depth: &mut u32, closing: bool) {
+    *depth = if closing { depth.saturating_sub(1) } else { *depth + 1 };
+}
+
+fn write_pango_spans(pango: &mut String, spans: &[Span]) {
+    for span in spans {
+        let (open, close) = match (span.emphasis, span.code) {
+            (true, true) => ("<i><tt>", "</tt></i>"),
+            (true, false) => ("<i>", "</i>"),
+            (false, true) => ("<tt>", "</tt>"),
+            (false, false) => ("", ""),
+        };
+
+        pango.push_str(open);
+        pango.push_str(&escape(&span.text));
+        pango.push_str(close);
+    }
+}
+
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+
+    for character in text.chars() {
+        match character {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            c => escaped.push(c),
+        }
+    }
+
+    escaped
+}
+
+fn unescape(text: &str) -> String {
+    if !text.contains('&') {
+        return text.to_owned();
+    }
+
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn plain(text: &str) -> Vec<Span> {
+        vec![Span { text: text.into(), emphasis: false, code: false }]
+    }
+
+    #[test]
+    fn paragraphs_and_lists() {
+        let description = Description::parse(
+            "<p>Intro  text</p><ol><li>One</li><li>Two</li></ol><p>Outro</p>"
+        );
+
+        assert_eq!(description.blocks, vec![
+            Block::Paragraph(plain("Intro text")),
+            Block::List { ordered: true, items: vec![plain("One"), plain("Two")] },
+            Block::Paragraph(plain("Outro")),
+        ]);
+
+        assert_eq!(description.to_pango(), "Intro text\n\n1. One\n2. Two\n\nOutro");
+    }
+
+    #[test]
+    fn nested_lists_are_flattened() {
+        let description = Description::parse(
+            "<ul><li>A<ol><li>B</li><li>C</li></ol></li><li>D</li></ul><p>After</p>"
+        );
+
+        assert_eq!(description.blocks, vec![
+            Block::List {
+                ordered: false,
+                items: vec![plain("A"), plain("B"), plain("C"), plain("D")],
+            },
+            Block::Paragraph(plain("After")),
+        ]);
+    }
+
+    #[test]
+    fn pango_escapes_text() {
+        let description = Description::parse("<p>Tom &amp; Jerry &lt;3 <em>a&gt;b</em></p>");
+        assert_eq!(description.to_plain_text(), "Tom & Jerry <3 a>b");
+        assert_eq!(description.to_pango(), "Tom &amp; Jerry &lt;3 <i>a&gt;b</i>");
+    }
+
+    #[test]
+    fn malformed_markup() {
+        let stray = Description::parse("</ul><p>Stray</li>");
+        assert_eq!(stray.blocks, vec![Block::Paragraph(plain("Stray"))]);
+
+        let unclosed = Description::parse("<p>Kept</p><p");
+        assert_eq!(unclosed.blocks, vec![Block::Paragraph(plain("Kept"))]);
+
+        assert_eq!(Description::parse("<p>Open <em>forever").to_pango(), "Open <i>forever</i>");
+        assert_eq!(Description::parse("Bare text").to_plain_text(), "Bare text");
+        assert_eq!(Description::parse("").blocks, vec![]);
+    }
+}
@@ -0,0 +1,142 @@
+//! Items that components provide, such as binaries, media types, and hardware support.
+//!
+//! Each item is stored in a reverse index under its kind, so that the components providing a
+//! binary or opening a media type may be looked up without visiting every component.
+
+use crate::dep11::appstream::Provides;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Deserialize, Serialize)]
+pub enum ProvidedKind {
+    Binary,
+    Library,
+    MediaType,
+    Modalias,
+    Font,
+    Python3,
+    Firmware,
+    DBus,
+    Id,
+}
+
+impl ProvidedKind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ProvidedKind::Binary => "binary",
+            ProvidedKind::Library => "library",
+            ProvidedKind::MediaType => "mediatype",
+            ProvidedKind::Modalias => "modalias",
+            ProvidedKind::Font => "font",
+            ProvidedKind::Python3 => "python3",
+            ProvidedKind::Firmware => "firmware",
+            ProvidedKind::DBus => "dbus",
+            ProvidedKind::Id => "id",
+        }
+    }
+
+    /// Normalizes a value for the index, as media types are case-insensitive.
+    pub fn normalize(self, value: &str) -> String {
+        match self {
+            ProvidedKind::MediaType => value.to_ascii_lowercase(),
+            _ => value.to_owned()
+        }
+    }
+
+    /// The key prefix in the reverse index of a value of this kind.
+    pub(crate) fn index_key(self, value: &str) -> Vec<u8> {
+        [self.as_str(), ":", &self.normalize(value)].concat().into_bytes()
+    }
+}
+
+/// Flattens the items that a DEP11 component provides into a list of each kind and value.
+pub fn flatten(provides: Provides) -> Vec<(ProvidedKind, String)> {
+    let mut items = Vec::new();
+
+    let mut extend = |kind: ProvidedKind, values: Option<Vec<String>>| {
+        items.extend(values.into_iter().flatten().map(|value| (kind, value)));
+    };
+
+    extend(ProvidedKind::Binary, provides.binaries);
+    extend(ProvidedKind::Library, provides.libraries);
+    extend(ProvidedKind::MediaType, provides.mediatypes);
+    extend(ProvidedKind::Modalias, provides.modaliases);
+    extend(ProvidedKind::Python3, provides.python3);
+    extend(ProvidedKind::Id, provides.ids);
+
+    let fonts = provides.fonts.into_iter().flatten().map(|font| font.name);
+    extend(ProvidedKind::Font, Some(fonts.collect()));
+
+    let firmware = provides.firmware.into_iter()
+        .flatten()
+        .filter_map(|firmware| firmware.file.or(firmware.guid));
+    extend(ProvidedKind::Firmware, Some(firmware.collect()));
+
+    let services = provides.dbus.into_iter().flatten().map(|dbus| dbus.service);
+    extend(ProvidedKind::DBus, Some(services.collect()));
+
+    items
+}
+
+/// Matches text against a shell-style pattern, where `*` matches any run of characters and
+/// `?` matches a single character, as used by modaliases.
+pub fn glob_match(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let text: Vec<char> = text.chars().collect();
+
+    let (mut p, mut t) = (0, 0);
+    // Position of the last `*` in the pattern, and of the text that it was matched against
+    let mut backtrack: Option<(usize, usize)> = None;
+
+    while t < text.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                backtrack = Some((p, t));
+                p += 1;
+            }
+            Some(&c) if c == '?' || c == text[t] => {
+                p += 1;
+                t += 1;
+            }
+            _ => match backtrack {
+                Some((star, matched)) => {
+                    p = star + 1;
+                    t = matched + 1;
+                    backtrack = Some((star, matched + 1));
+                }
+                None => return false
+            }
+        }
+    }
+
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::glob_match;
+
+    #[test]
+    fn globs() {
+        assert!(glob_match("*", ""));
+        assert!(glob_match("*", "usb:v046Dp0825"));
+        assert!(glob_match("usb:v046Dp08??", "usb:v046Dp0825"));
+        assert!(!glob_match("usb:v046Dp08??", "usb:v046Dp082"));
+        assert!(glob_match("usb:v*p0825*", "usb:v046Dp0825d0010"));
+        assert!(glob_match("*ic0Eisc01*", "usb:v046Dp0825dc00ic0Eisc01ip00"));
+    }
+
+    #[test]
+    fn anchored_tail() {
+        assert!(glob_match("pci:*d1C82", "pci:v10DEd1C82"));
+        assert!(!glob_match("pci:*d1C82", "pci:v10DEd1C82sv1028"));
+        assert!(glob_match("pci:*d1C82*", "pci:v10DEd1C82sv1028"));
+        assert!(glob_match("a*b*b", "abbb"));
+    }
+
+    #[test]
+    fn no_match() {
+        assert!(!glob_match("pci:v10DE*", "usb:v10DE"));
+        assert!(!glob_match("pci", "pci:v10DE"));
+        assert!(!glob_match("pci:v10DE?", "pci:v10DE"));
+        assert!(!glob_match("", "pci"));
+    }
+}
@@ -0,0 +1,130 @@
+//! Releases of components, for showing what is new in each version.
+//!
+//! Only the `Releases` block of DEP11 catalogs is read. Releases in AppStream XML catalogs are
+//! not, since no XML catalog is parsed yet: `flatpak::read_xml` is still a stub.
+
+use crate::dep11::appstream::Dep11Release;
+use crate::locale::{self, Locale, Translations};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize, Serialize)]
+pub enum ReleaseKind {
+    Stable,
+    Development,
+}
+
+/// How important it is to update to a release, from least to most urgent.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Deserialize, Serialize)]
+pub enum Urgency {
+    Unknown,
+    Low,
+    Medium,
+    High,
+    Critical,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct Release {
+    pub version: String,
+    /// Seconds since the Unix epoch at which the version was released.
+    pub timestamp: Option<i64>,
+    pub kind: ReleaseKind,
+    pub urgency: Urgency,
+    /// Release notes in AppStream's description markup, keyed by language.
+    pub description: Translations,
+}
+
+impl Release {
+    pub fn description(&self, locale: &Locale) -> Option<&str> {
+        locale.resolve(&self.description).map(String::as_str)
+    }
+}
+
+impl From<Dep11Release> for Release {
+    fn from(release: Dep11Release) -> Self {
+        Self {
+            version: release.version,
+            timestamp: release.unix_timestamp,
+            kind: parse_kind(release.type_.as_deref().unwrap_or_default()),
+            urgency: parse_urgency(release.urgency.as_deref().unwrap_or_default()),
+            description: release.description.map(locale::normalize_keys).unwrap_or_default(),
+        }
+    }
+}
+
+/// Sorts releases from newest to oldest. Releases without a timestamp keep their position
+/// relative to each other, after those which have one.
+pub fn sort(releases: &mut [Release]) {
+    releases.sort_by_key(|release| std::cmp::Reverse(release.timestamp));
+}
+
+pub fn parse_kind(kind: &str) -> ReleaseKind {
+    if kind.eq_ignore_ascii_case("development") {
+        ReleaseKind::Development
+    } else {
+        ReleaseKind::Stable
+    }
+}
+
+pub fn parse_urgency(urgency: &str) -> Urgency {
+    match urgency.to_ascii_lowercase().as_str() {
+        "low" => Urgency::Low,
+        "medium" => Urgency::Medium,
+        "high" => Urgency::High,
+        "critical" => Urgency::Critical,
+        _ => Urgency::Unknown
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn release(version: &str, timestamp: Option<i64>) -> Release {
+        Release {
+            version: version.into(),
+            timestamp,
+            kind: ReleaseKind::Stable,
+            urgency: Urgency::Unknown,
+            description: Translations::new(),
+        }
+    }
+
+    #[test]
+    fn sort_puts_undated_releases_last() {
+        let mut releases = vec![
+            release("1.0", Some(100)),
+            release("undated-a", None),
+            release("3.0", Some(300)),
+            release("undated-b", None),
+            release("2.0", Some(200)),
+        ];
+
+        sort(&mut releases);
+
+        let versions: Vec<&str> = releases.iter().map(|r| r.version.as_str()).collect();
+        assert_eq!(versions, ["3.0", "2.0", "1.0", "undated-a", "undated-b"]);
+    }
+
+    #[test]
+    fn from_dep11() {
+        let release = Release::from(Dep11Release {
+            version: "2.0".into(),
+            type_: Some("Development".into()),
+            unix_timestamp: Some(1_600_000_000),
+            urgency: Some("HIGH".into()),
+            description: Some(std::iter::once(("C".to_owned(), "<p>New</p>".to_owned())).collect()),
+        });
+
+        assert_eq!(release.kind, ReleaseKind::Development);
+        assert_eq!(release.urgency, Urgency::High);
+        assert_eq!(release.description(&Locale::new("de")), Some("<p>New</p>"));
+
+        let release = Release::from(Dep11Release {
+            version: "1.0".into(),
+            ..Dep11Release::default()
+        });
+        assert_eq!(release.kind, ReleaseKind::Stable);
+        assert_eq!(release.urgency, Urgency::Unknown);
+        assert_eq!(release.timestamp, None);
+    }
+}
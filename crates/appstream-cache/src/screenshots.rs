@@ -0,0 +1,47 @@
+//! Screenshots and videos of components, whose URLs are relative to their origin's media server.
+
+use crate::locale::Locale;
+
+pub use crate::dep11::appstream::{Screenshot, ScreenshotImage, ScreenshotVideo};
+
+/// Joins a URL with the media base URL of its origin, unless it is already absolute.
+pub fn resolve_url(base_url: Option<&str>, url: &str) -> String {
+    match base_url {
+        Some(base_url) if !url.contains("://") => {
+            [base_url.trim_end_matches('/'), "/", url.trim_start_matches('/')].concat()
+        }
+        _ => url.to_owned()
+    }
+}
+
+impl Screenshot {
+    pub fn is_default(&self) -> bool {
+        self.default.unwrap_or(false)
+    }
+
+    pub fn caption(&self, locale: &Locale) -> Option<&str> {
+        locale.resolve(self.caption.as_ref()?).map(String::as_str)
+    }
+
+    /// The smallest thumbnail which is at least as wide as requested, or else the widest.
+    pub fn thumbnail(&self, width: u32) -> Option<&ScreenshotImage> {
+        let thumbnails = self.thumbnails.as_deref().unwrap_or_default();
+
+        thumbnails.iter()
+            .filter(|thumbnail| thumbnail.width.unwrap_or(0) >= width)
+            .min_by_key(|thumbnail| thumbnail.width)
+            .or_else(|| thumbnails.iter().max_by_key(|thumbnail| thumbnail.width))
+    }
+
+    /// Resolves every URL of the screenshot against the media base URL.
+    pub(crate) fn resolve(&mut self, base_url: Option<&str>) {
+        let images = self.thumbnails.iter_mut().flatten().chain(self.source_image.as_mut());
+        for image in images {
+            image.url = resolve_url(base_url, &image.url);
+        }
+
+        for video in self.videos.iter_mut().flatten() {
+            video.url = resolve_url(base_url, &video.url);
+        }
+    }
+}
@@ -0,0 +1,53 @@
+//! Paginated access to the results of a search.
+
+use crate::{AppEntry, Database};
+
+/// Ranked results of a search, from which apps are fetched one page at a time.
+///
+/// Ranking only needs the IDs of the apps which matched, so the cursor is cheap to create.
+/// Details and variants of each app are fetched as its page is requested.
+pub struct SearchCursor {
+    apps: Vec<String>,
+    position: usize,
+    suggestion: Option<String>,
+}
+
+impl SearchCursor {
+    pub(crate) fn new(apps: Vec<String>) -> Self {
+        Self { apps, position: 0, suggestion: None }
+    }
+
+    pub(crate) fn with_suggestion(apps: Vec<String>, suggestion: Option<String>) -> Self {
+        Self { apps, position: 0, suggestion }
+    }
+
+    /// The corrected free text that was searched for instead, if the query matched nothing.
+    pub fn suggestion(&self) -> Option<&str> {
+        self.suggestion.as_deref()
+    }
+
+    /// Total number of apps which matched.
+    pub fn len(&self) -> usize {
+        self.apps.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.apps.is_empty()
+    }
+
+    /// Checks if every page has been fetched.
+    pub fn is_exhausted(&self) -> bool {
+        self.position >= self.apps.len()
+    }
+
+    /// Fetches up to `size` apps following those of the previous page.
+    pub fn next_page(&mut self, db: &Database, size: usize) -> Vec<AppEntry> {
+        let end = self.apps.len().min(self.position + size);
+        let page = &self.apps[self.position..end];
+        self.position = end;
+
+        page.iter()
+            .filter_map(|id| db.app_entry(id.clone()))
+            .collect()
+    }
+}
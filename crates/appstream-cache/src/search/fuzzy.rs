@@ -0,0 +1,120 @@
+//! Approximate matching of misspelled terms against the index vocabulary.
+//!
+//! Candidate terms are found through the trigrams they share with the misspelled term, and
+//! then verified by their edit distance to it.
+
+/// Trigrams of a term, padded so that the first and last characters form their own grams.
+pub fn trigrams(term: &str) -> Vec<String> {
+    let padded: Vec<char> = std::iter::once('$')
+        .chain(term.chars())
+        .chain(std::iter::once('$'))
+        .collect();
+
+    let mut grams: Vec<String> = padded.windows(3).map(|gram| gram.iter().collect()).collect();
+    grams.sort();
+    grams.dedup();
+    grams
+}
+
+/// The most edits that a term of this length may have to still be considered a match.
+pub fn max_distance(term: &str) -> usize {
+    match term.chars().count() {
+        0..=2 => 0,
+        3..=5 => 1,
+        _ => 2,
+    }
+}
+
+/// Optimal string alignment distance between two terms, where adjacent transpositions count
+/// as a single edit. Returns `None` as soon as the distance is known to exceed `max`.
+pub fn distance(a: &str, b: &str, max: usize) -> Option<usize> {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+
+    if a.len().max(b.len()) - a.len().min(b.len()) > max {
+        return None;
+    }
+
+    let width = b.len() + 1;
+    let mut rows = vec![0usize; width * (a.len() + 1)];
+
+    for (j, cell) in rows.iter_mut().take(width).enumerate() {
+        *cell = j;
+    }
+
+    for i in 1..=a.len() {
+        rows[i * width] = i;
+        let mut row_min = i;
+
+        for j in 1..=b.len() {
+            let cost = if a[i - 1] == b[j - 1] { 0 } else { 1 };
+
+            let mut value = (rows[(i - 1) * width + j] + 1)
+                .min(rows[i * width + j - 1] + 1)
+                .min(rows[(i - 1) * width + j - 1] + cost);
+
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                value = value.min(rows[(i - 2) * width + j - 2] + 1);
+            }
+
+            rows[i * width + j] = value;
+            row_min = row_min.min(value);
+        }
+
+        if row_min > max {
+            return None;
+        }
+    }
+
+    Some(rows[a.len() * width + b.len()]).filter(|&distance| distance <= max)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn distance_counts_edits() {
+        assert_eq!(distance("firefox", "firefox", 2), Some(0));
+        assert_eq!(distance("firefx", "firefox", 2), Some(1));
+        assert_eq!(distance("firefoxx", "firefox", 2), Some(1));
+        assert_eq!(distance("firafox", "firefox", 2), Some(1));
+        assert_eq!(distance("", "abc", 3), Some(3));
+    }
+
+    #[test]
+    fn distance_counts_transpositions_once() {
+        assert_eq!(distance("thunderbrid", "thunderbird", 2), Some(1));
+        assert_eq!(distance("ab", "ba", 1), Some(1));
+        // Optimal string alignment does not edit a substring more than once
+        assert_eq!(distance("ca", "abc", 3), Some(3));
+    }
+
+    #[test]
+    fn distance_stops_beyond_the_bound() {
+        assert_eq!(distance("gimp", "inkscape", 2), None);
+        assert_eq!(distance("kate", "kite", 0), None);
+        assert_eq!(distance("libreofice", "libreoffice", 1), Some(1));
+        assert_eq!(distance("lbreofice", "libreoffice", 1), None);
+    }
+
+    #[test]
+    fn distance_compares_characters() {
+        assert_eq!(distance("café", "cafe", 1), Some(1));
+        assert_eq!(distance("телеграм", "телеграмм", 1), Some(1));
+        assert_eq!(distance("日本語", "日本", 1), Some(1));
+    }
+
+    #[test]
+    fn max_distance_grows_with_length() {
+        assert_eq!(max_distance("vi"), 0);
+        assert_eq!(max_distance("gimp"), 1);
+        assert_eq!(max_distance("éèà"), 1);
+        assert_eq!(max_distance("firefox"), 2);
+    }
+
+    #[test]
+    fn trigrams_are_padded() {
+        assert_eq!(trigrams("ab"), vec!["$ab", "ab$"]);
+    }
+}
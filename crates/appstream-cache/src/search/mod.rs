@@ -0,0 +1,405 @@
+//! Tokenized inverted index over the text fields of each component.
+//!
+//! Postings are stored in a multimap tree keyed by term and entity, where the value is the
+//! relevance of that term to the entity. Relevance is the sum of each field's weight, scaled
+//! logarithmically by how often the term occurs within that field.
+//!
+//! Terms from names and keywords additionally form the vocabulary that misspelled queries are
+//! corrected against.
+
+pub mod cursor;
+pub mod fuzzy;
+pub mod normalize;
+pub mod query;
+
+use crate::locale::{Locale, UNTRANSLATED};
+use crate::{multimap_key, split_multimap_key, Entity};
+use std::collections::HashMap;
+
+const KEY_TERMS: &str = "terms";
+const KEY_VOCABULARY: &str = "vocabulary";
+const KEY_TRIGRAMS: &str = "trigrams";
+const KEY_COMPLETIONS: &str = "completions";
+
+/// Penalty applied to terms which only match the query as a prefix.
+const PREFIX_PENALTY: f32 = 0.5;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Field {
+    Name,
+    Summary,
+    Keywords,
+    Categories,
+    Description,
+    /// Names in languages outside of the user's locale.
+    ForeignName,
+    /// Keywords in languages outside of the user's locale.
+    ForeignKeywords,
+    /// Untranslated names, when the user's locale has languages of its own.
+    UntranslatedName,
+    /// Untranslated keywords, when the user's locale has languages of its own.
+    UntranslatedKeywords,
+}
+
+const FIELDS: usize = 9;
+
+impl Field {
+    const ALL: [Field; FIELDS] = [
+        Field::Name,
+        Field::Summary,
+        Field::Keywords,
+        Field::Categories,
+        Field::Description,
+        Field::ForeignName,
+        Field::ForeignKeywords,
+        Field::UntranslatedName,
+        Field::UntranslatedKeywords,
+    ];
+
+    pub fn weight(self) -> f32 {
+        match self {
+            Field::Name => 10.0,
+            Field::UntranslatedName => 8.0,
+            Field::Keywords => 6.0,
+            Field::ForeignName => 5.0,
+            Field::UntranslatedKeywords => 4.5,
+            Field::Summary => 4.0,
+            Field::Categories => 3.0,
+            Field::ForeignKeywords => 2.0,
+            Field::Description => 1.0,
+        }
+    }
+
+    /// Terms of names and keywords, in any language, form the vocabulary for corrections.
+    fn is_vocabulary(self) -> bool {
+        !matches!(self, Field::Summary | Field::Categories | Field::Description)
+    }
+}
+
+/// How closely the language of a translation matches the user's locale.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Affinity {
+    Foreign,
+    Untranslated,
+    Preferred,
+}
+
+impl Affinity {
+    pub fn of(locale: &Locale, language: &str) -> Self {
+        let chain = locale.chain();
+
+        if language == UNTRANSLATED && chain.len() > 1 {
+            Affinity::Untranslated
+        } else if chain.iter().any(|l| l == language) {
+            Affinity::Preferred
+        } else {
+            Affinity::Foreign
+        }
+    }
+
+    pub fn name_field(self) -> Field {
+        match self {
+            Affinity::Preferred => Field::Name,
+            Affinity::Untranslated => Field::UntranslatedName,
+            Affinity::Foreign => Field::ForeignName,
+        }
+    }
+
+    pub fn keywords_field(self) -> Field {
+        match self {
+            Affinity::Preferred => Field::Keywords,
+            Affinity::Untranslated => Field::UntranslatedKeywords,
+            Affinity::Foreign => Field::ForeignKeywords,
+        }
+    }
+}
+
+/// Splits text into normalized alphanumeric terms.
+///
+/// Indexed text and queries are both tokenized this way, so they match regardless of case,
+/// compatibility forms, and diacritics. Markup must be converted to plain text beforehand.
+pub fn tokenize(text: &str) -> Vec<String> {
+    normalize::fold(text)
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|term| !term.is_empty())
+        .map(String::from)
+        .collect()
+}
+
+/// Collects the terms of a single component before they are written to the index.
+#[derive(Default)]
+pub struct Document {
+    counts: HashMap<String, [u32; FIELDS]>,
+}
+
+impl Document {
+    /// Adds the terms of a field, along with romanized forms of any terms written in a
+    /// non-Latin script.
+    pub fn add(&mut self, field: Field, text: &str) {
+        for term in tokenize(text) {
+            if let Some(romanized) = normalize::transliterate(&term) {
+                for term in tokenize(&romanized) {
+                    self.counts.entry(term).or_default()[field as usize] += 1;
+                }
+            }
+
+            self.counts.entry(term).or_default()[field as usize] += 1;
+        }
+    }
+}
+
+pub struct SearchIndex {
+    /// Postings of each term, keyed by term and entity.
+    pub terms: sled::Tree,
+    /// Number of components whose names or keywords contain each term.
+    pub vocabulary: sled::Tree,
+    /// Vocabulary terms keyed by each of their trigrams.
+    pub trigrams: sled::Tree,
+    /// Display names keyed by their folded form and entity, for completing whole names.
+    pub completions: sled::Tree,
+}
+
+impl SearchIndex {
+    pub fn open(db: &sled::Db) -> Self {
+        Self {
+            terms: db.open_tree(KEY_TERMS).unwrap(),
+            vocabulary: db.open_tree(KEY_VOCABULARY).unwrap(),
+            trigrams: db.open_tree(KEY_TRIGRAMS).unwrap(),
+            completions: db.open_tree(KEY_COMPLETIONS).unwrap(),
+        }
+    }
+
+    /// Removes every document from the index, such as before reindexing in another language.
+    pub fn clear(&self) {
+        let _ = self.terms.clear();
+        let _ = self.vocabulary.clear();
+        let _ = self.trigrams.clear();
+        let _ = self.completions.clear();
+    }
+
+    /// Writes the postings of a document to the index.
+    pub fn insert(&self, document: &Document, entity: Entity) {
+        for (term, counts) in &document.counts {
+            let score: f32 = Field::ALL.iter()
+                .zip(counts.iter())
+                .filter(|(_, &count)| count != 0)
+                .map(|(field, &count)| field.weight() * (1.0 + (count as f32).ln()))
+                .sum();
+
+            let _ = self.terms.insert(multimap_key(term.as_bytes(), entity), &score.to_ne_bytes());
+
+            let in_vocabulary = Field::ALL.iter()
+                .zip(counts.iter())
+                .any(|(field, &count)| count != 0 && field.is_vocabulary());
+
+            if in_vocabulary {
+                self.add_to_vocabulary(term);
+            }
+        }
+    }
+
+    fn add_to_vocabulary(&self, term: &str) {
+        let previous = self.vocabulary.fetch_and_update(term.as_bytes(), |count| {
+            let count = count.map_or(0, read_u32) + 1;
+            Some(count.to_ne_bytes().to_vec())
+        });
+
+        if let Ok(None) = previous {
+            for gram in fuzzy::trigrams(term) {
+                let mut key = gram.into_bytes();
+                key.push(0);
+                key.extend_from_slice(term.as_bytes());
+                let _ = self.trigrams.insert(key, &[]);
+            }
+        }
+    }
+
+    /// Makes a name available to `complete_name`.
+    pub fn insert_completion(&self, name: &str, entity: Entity) {
+        let key = multimap_key(normalize::fold(name).as_bytes(), entity);
+        let _ = self.completions.insert(key, name.as_bytes());
+    }
+
+    /// Names of the accepted entities which begin with the input, in alphabetical order.
+    pub fn complete_name(
+        &self,
+        input: &str,
+        limit: usize,
+        accept: impl Fn(Entity) -> bool
+    ) -> Vec<(String, Entity)> {
+        self.completions.scan_prefix(normalize::fold(input).as_bytes())
+            .filter_map(Result::ok)
+            .filter_map(|(key, name)| {
+                let (_, entity) = split_multimap_key(&key)?;
+                if !accept(entity) {
+                    return None;
+                }
+
+                let name = String::from_utf8(name.to_vec()).ok()?;
+                Some((name, entity))
+            })
+            .take(limit)
+            .collect()
+    }
+
+    /// Vocabulary terms which begin with the given term, along with the number of components
+    /// that each occurs in. Only the `limit` most frequent terms are returned.
+    pub fn complete_term(&self, term: &str, limit: usize) -> Vec<(String, u32)> {
+        let mut terms: Vec<(String, u32)> = self.vocabulary.scan_prefix(term.as_bytes())
+            .filter_map(Result::ok)
+            .filter_map(|(term, count)| {
+                String::from_utf8(term.to_vec()).ok().map(|term| (term, read_u32(&count)))
+            })
+            .collect();
+
+        terms.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        terms.truncate(limit);
+        terms
+    }
+
+    /// Checks if any indexed term equals, or begins with, the given term.
+    pub fn contains(&self, term: &str) -> bool {
+        self.terms.scan_prefix(term.as_bytes()).next().is_some()
+    }
+
+    /// Finds the vocabulary term closest to a misspelled term, along with its edit distance and
+    /// the number of components that it occurs in.
+    pub fn correct(&self, term: &str) -> Option<(String, usize, u32)> {
+        let max = fuzzy::max_distance(term);
+        if max == 0 {
+            return None;
+        }
+
+        let grams = fuzzy::trigrams(term);
+        let required = grams.len().saturating_sub(3 * max).max(1);
+
+        let mut shared: HashMap<Vec<u8>, usize> = HashMap::new();
+        for gram in &grams {
+            let mut prefix = gram.as_bytes().to_vec();
+            prefix.push(0);
+
+            for key in self.trigrams.scan_prefix(&prefix).keys().filter_map(Result::ok) {
+                *shared.entry(key[prefix.len()..].to_vec()).or_insert(0) += 1;
+            }
+        }
+
+        let mut best: Option<(String, usize, u32)> = None;
+
+        for (candidate, count) in shared {
+            if count < required {
+                continue
+            }
+
+            let candidate = match String::from_utf8(candidate) {
+                Ok(candidate) => candidate,
+                Err(_) => continue
+            };
+
+            if let Some(distance) = fuzzy::distance(term, &candidate, max) {
+                let frequency = self.vocabulary.get(candidate.as_bytes())
+                    .ok()
+                    .flatten()
+                    .map_or(0, |count| read_u32(&count));
+
+                let better = best.as_ref().is_none_or(|(_, d, f)| {
+                    distance < *d || (distance == *d && frequency > *f)
+                });
+
+                if better {
+                    best = Some((candidate, distance, frequency));
+                }
+            }
+        }
+
+        best
+    }
+
+    /// Scores every entity which matches all terms in the query.
+    ///
+    /// Each term of the query also matches indexed terms that it is a prefix of, so that results
+    /// are found while the query is still being typed.
+    pub fn query(&self, query: &str) -> HashMap<Entity, f32> {
+        self.score(query, true)
+    }
+
+    /// Scores every entity which contains all terms in the query as whole terms, such as to
+    /// exclude them from results.
+    pub fn query_exact(&self, query: &str) -> HashMap<Entity, f32> {
+        self.score(query, false)
+    }
+
+    fn score(&self, query: &str, prefixes: bool) -> HashMap<Entity, f32> {
+        let mut scores: Option<HashMap<Entity, f32>> = None;
+
+        for token in tokenize(query) {
+            let mut matches: HashMap<Entity, f32> = HashMap::new();
+
+            for (key, value) in self.terms.scan_prefix(token.as_bytes()).filter_map(Result::ok) {
+                if let Some((term, entity)) = split_multimap_key(&key) {
+                    let mut score = [0u8; 4];
+                    score.copy_from_slice(&value[..4]);
+                    let mut score = f32::from_ne_bytes(score);
+
+                    if term != token.as_bytes() {
+                        if !prefixes {
+                            continue
+                        }
+
+                        score *= PREFIX_PENALTY;
+                    }
+
+                    let best = matches.entry(entity).or_insert(0.0);
+                    if score > *best {
+                        *best = score;
+                    }
+                }
+            }
+
+            scores = Some(match scores {
+                None => matches,
+                Some(previous) => previous.into_iter()
+                    .filter_map(|(entity, score)| matches.get(&entity).map(|s| (entity, score + s)))
+                    .collect()
+            });
+        }
+
+        scores.unwrap_or_default()
+    }
+}
+
+fn read_u32(bytes: &[u8]) -> u32 {
+    let mut array = [0u8; 4];
+    array.copy_from_slice(&bytes[..4]);
+    u32::from_ne_bytes(array)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn untranslated_ranks_below_locale() {
+        let locale = Locale::new("de_DE");
+        assert_eq!(Affinity::of(&locale, "de"), Affinity::Preferred);
+        assert_eq!(Affinity::of(&locale, "C"), Affinity::Untranslated);
+        assert_eq!(Affinity::of(&locale, "fr"), Affinity::Foreign);
+        assert!(Field::Name.weight() > Field::UntranslatedName.weight());
+        assert!(Field::UntranslatedName.weight() > Field::ForeignName.weight());
+        assert!(Field::Keywords.weight() > Field::UntranslatedKeywords.weight());
+        assert!(Field::UntranslatedKeywords.weight() > Field::ForeignKeywords.weight());
+    }
+
+    #[test]
+    fn tokenize_keeps_angle_brackets_as_separators() {
+        assert_eq!(tokenize("Tom & Jerry <3 cartoons and more"), [
+            "tom", "jerry", "3", "cartoons", "and", "more"
+        ]);
+        assert_eq!(tokenize("a<b>c"), ["a", "b", "c"]);
+        assert_eq!(tokenize("  Café-Bar!  "), ["cafe", "bar"]);
+    }
+
+    #[test]
+    fn untranslated_is_preferred_without_locale() {
+        assert_eq!(Affinity::of(&Locale::new("C"), "C"), Affinity::Preferred);
+    }
+}
@@ -0,0 +1,59 @@
+//! Normalization of text so that matching ignores case, compatibility forms, and diacritics.
+
+use unicode_normalization::char::is_combining_mark;
+use unicode_normalization::UnicodeNormalization;
+
+/// Applies NFKD decomposition, strips the combining marks that it separates from their base
+/// characters, and then case folds what remains.
+pub fn fold(text: &str) -> String {
+    let mut folded = String::with_capacity(text.len());
+
+    for character in text.nfkd().filter(|&c| !is_combining_mark(c)) {
+        match character {
+            'ß' | 'ẞ' => folded.push_str("ss"),
+            'ς' => folded.push('σ'),
+            c => folded.extend(c.to_lowercase()),
+        }
+    }
+
+    folded
+}
+
+/// Romanizes a folded term written in a non-Latin script, so that it may also be found by
+/// queries typed on a Latin keyboard. Returns `None` for terms which are already ASCII.
+pub fn transliterate(term: &str) -> Option<String> {
+    if term.is_ascii() {
+        return None;
+    }
+
+    let romanized = deunicode::deunicode(term).to_lowercase();
+
+    if romanized.is_empty() || romanized == term {
+        None
+    } else {
+        Some(romanized)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn folding() {
+        assert_eq!(fold("Firefox"), "firefox");
+        assert_eq!(fold("Café"), "cafe");
+        assert_eq!(fold("CAFÉ"), fold("cafe\u{301}"));
+        assert_eq!(fold("Straße"), "strasse");
+        assert_eq!(fold("STRAẞE"), "strasse");
+        assert_eq!(fold("ﬁle"), "file");
+        assert_eq!(fold("ΟΔΟΣ"), fold("οδος"));
+    }
+
+    #[test]
+    fn transliteration() {
+        assert_eq!(transliterate("firefox"), None);
+        assert_eq!(transliterate("фаерфокс").as_deref(), Some("faerfoks"));
+        assert_eq!(transliterate("ελληνικα").as_deref(), Some("ellenika"));
+    }
+}
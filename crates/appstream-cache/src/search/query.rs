@@ -0,0 +1,163 @@
+//! Parsing of search queries, where free text may be combined with qualifiers.
+//!
+//! Qualifiers take the form of `key:value`, such as `category:Game` or `installed:yes`, and
+//! both qualifiers and free text terms may be negated with a leading `-`. Values containing
+//! spaces may be wrapped in double quotes.
+//!
+//! The `kind` qualifier accepts a component kind, such as `font`, or `app` for any kind of
+//! application. The `license` qualifier accepts either a license identifier, or one of the
+//! `free`, `open-source`, `proprietary`, and `approved` classifications.
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Qualifier {
+    Type(String),
+    Category(String),
+    License(String),
+    Origin(String),
+    Installed(bool),
+}
+
+impl Qualifier {
+    fn parse(key: &str, value: &str) -> Option<Self> {
+        let qualifier = match key.to_ascii_lowercase().as_str() {
+            "type" | "kind" => Qualifier::Type(value.to_owned()),
+            "category" => Qualifier::Category(value.to_owned()),
+            "license" => Qualifier::License(value.to_owned()),
+            "origin" => Qualifier::Origin(value.to_owned()),
+            "installed" => Qualifier::Installed(match value.to_ascii_lowercase().as_str() {
+                "yes" | "true" | "1" => true,
+                "no" | "false" | "0" => false,
+                _ => return None
+            }),
+            _ => return None
+        };
+
+        Some(qualifier)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Filter {
+    pub qualifier: Qualifier,
+    pub negated: bool,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Query {
+    /// Free text to search the full-text index for.
+    pub text: String,
+    /// Free text terms which results must not match.
+    pub excluded: Vec<String>,
+    pub filters: Vec<Filter>,
+}
+
+impl Query {
+    pub fn parse(input: &str) -> Self {
+        let mut query = Query::default();
+        let mut text: Vec<String> = Vec::new();
+
+        for word in split_words(input) {
+            // A lone `-` negates nothing, and has no terms to search for
+            if word == "-" {
+                continue
+            }
+
+            let (negated, word) = match word.strip_prefix('-') {
+                Some(rest) if !rest.is_empty() => (true, rest.to_owned()),
+                _ => (false, word)
+            };
+
+            let qualifier = word.find(':')
+                .and_then(|pos| Qualifier::parse(&word[..pos], &word[pos + 1..]));
+
+            match qualifier {
+                Some(qualifier) => query.filters.push(Filter { qualifier, negated }),
+                None if negated => query.excluded.push(word),
+                None => text.push(word)
+            }
+        }
+
+        query.text = text.join(" ");
+        query
+    }
+}
+
+/// Splits the input on whitespace, except for whitespace that is within double quotes.
+fn split_words(input: &str) -> Vec<String> {
+    let mut words = Vec::new();
+    let mut word = String::new();
+    let mut quoted = false;
+
+    for character in input.chars() {
+        match character {
+            '"' => quoted = !quoted,
+            c if c.is_whitespace() && !quoted => {
+                if !word.is_empty() {
+                    words.push(std::mem::take(&mut word));
+                }
+            }
+            c => word.push(c)
+        }
+    }
+
+    if !word.is_empty() {
+        words.push(word);
+    }
+
+    words
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn filter(qualifier: Qualifier, negated: bool) -> Filter {
+        Filter { qualifier, negated }
+    }
+
+    #[test]
+    fn text_and_qualifiers() {
+        let query = Query::parse("image editor category:Graphics installed:no");
+        assert_eq!(query.text, "image editor");
+        assert!(query.excluded.is_empty());
+        assert_eq!(query.filters, [
+            filter(Qualifier::Category("Graphics".into()), false),
+            filter(Qualifier::Installed(false), false),
+        ]);
+    }
+
+    #[test]
+    fn negation() {
+        let query = Query::parse("browser -chromium -kind:font -ORIGIN:flathub");
+        assert_eq!(query.text, "browser");
+        assert_eq!(query.excluded, ["chromium"]);
+        assert_eq!(query.filters, [
+            filter(Qualifier::Type("font".into()), true),
+            filter(Qualifier::Origin("flathub".into()), true),
+        ]);
+    }
+
+    #[test]
+    fn quoted_values() {
+        let query = Query::parse("license:\"GPL-3.0 OR MIT\" \"text editor\" -\"web browser\"");
+        assert_eq!(query.text, "text editor");
+        assert_eq!(query.excluded, ["web browser"]);
+        assert_eq!(query.filters, [filter(Qualifier::License("GPL-3.0 OR MIT".into()), false)]);
+    }
+
+    #[test]
+    fn unknown_qualifiers_are_text() {
+        let query = Query::parse("http://example.com installed:maybe -color:red");
+        assert_eq!(query.text, "http://example.com installed:maybe");
+        assert_eq!(query.excluded, ["color:red"]);
+        assert!(query.filters.is_empty());
+    }
+
+    #[test]
+    fn lone_dash() {
+        let query = Query::parse("image - editor -");
+        assert_eq!(query.text, "image editor");
+        assert!(query.excluded.is_empty());
+        assert_eq!(Query::parse("-"), Query::default());
+    }
+}
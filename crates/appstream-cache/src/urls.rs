@@ -0,0 +1,63 @@
+//! Links to a project's resources, such as its homepage and bug tracker.
+
+/// The kinds of URL defined by AppStream, in the order that they should be presented.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum UrlKind {
+    Homepage,
+    Help,
+    Faq,
+    Bugtracker,
+    Contact,
+    Donation,
+    Translate,
+    Contribute,
+    VcsBrowser,
+}
+
+impl UrlKind {
+    pub const ALL: [UrlKind; 9] = [
+        UrlKind::Homepage,
+        UrlKind::Help,
+        UrlKind::Faq,
+        UrlKind::Bugtracker,
+        UrlKind::Contact,
+        UrlKind::Donation,
+        UrlKind::Translate,
+        UrlKind::Contribute,
+        UrlKind::VcsBrowser,
+    ];
+
+    /// Parses the kind of a URL, as it is written in appstream data.
+    pub fn parse(kind: &str) -> Option<Self> {
+        UrlKind::ALL.iter().copied().find(|k| kind.eq_ignore_ascii_case(k.as_str()))
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            UrlKind::Homepage => "homepage",
+            UrlKind::Help => "help",
+            UrlKind::Faq => "faq",
+            UrlKind::Bugtracker => "bugtracker",
+            UrlKind::Contact => "contact",
+            UrlKind::Donation => "donation",
+            UrlKind::Translate => "translate",
+            UrlKind::Contribute => "contribute",
+            UrlKind::VcsBrowser => "vcs-browser",
+        }
+    }
+
+    /// A label for a button which opens the URL.
+    pub fn label(self) -> &'static str {
+        match self {
+            UrlKind::Homepage => "Website",
+            UrlKind::Help => "Help",
+            UrlKind::Faq => "FAQ",
+            UrlKind::Bugtracker => "Report an Issue",
+            UrlKind::Contact => "Contact",
+            UrlKind::Donation => "Donate",
+            UrlKind::Translate => "Translate",
+            UrlKind::Contribute => "Contribute",
+            UrlKind::VcsBrowser => "Source Code",
+        }
+    }
+}
@@ -23,12 +23,12 @@ impl Decoder for YamlSplitter {
             }
         };
 
-        return Ok(None);
+        Ok(None)
     }
 
     fn decode_eof(&mut self, src: &mut BytesMut) -> Result<Option<Self::Item>, Self::Error> {
         let mut bytes = src.split();
-        return Ok(
+        Ok(
             if bytes.is_empty() {
                 None
             } else if bytes.ends_with(b"\n") {
@@ -36,6 +36,6 @@ impl Decoder for YamlSplitter {
             } else {
                 Some(bytes)
             }
-        );
+        )
     }
 }
@@ -1,32 +1,46 @@
-use appstream_cache::Database;
+use appstream_cache::kind::ComponentKind;
+use appstream_cache::locale::Locale;
+use appstream_cache::{Database, SearchCursor};
 use crate::Event;
 use crate::utils;
 use crate::widgets::AppListing;
 use gtk::prelude::*;
 use smol::channel::Sender;
-use std::collections::HashMap;
-use std::rc::Rc;
+use std::path::PathBuf;
+use std::time::Duration;
 
-struct AppMeta {
-    pub discovered: HashMap<Rc<str>, u32>,
-}
+// Number of search results to display at a time
+const PAGE_SIZE: usize = 50;
+
+// How many times to try opening the cache while another process holds its lock
+const OPEN_ATTEMPTS: u32 = 20;
 
 pub struct App {
     list: gtk::ListBox,
     search: gtk::SearchEntry,
 
+    // Suggestions shown beneath the search entry as the user types
+    completions: gtk::ListStore,
+
+    // Tells the user when their query was corrected, with a link to search for it as typed
+    correction: gtk::Label,
+
+    // Results of the last search which have yet to be displayed
+    results: Option<SearchCursor>,
+
     // Where all the appstream-related information is stored
     db: Database,
 
     // Send events to the application's event handler
     tx: Sender<Event>,
-
-    app_list: HashMap<String, AppMeta>
 }
 
 impl App {
-    pub fn new(app: &gtk::Application, tx: Sender<Event>) -> Self {
-        let db = Database::new("target/db".into(), "en_US".to_owned());
+    pub fn new(app: &gtk::Application, tx: Sender<Event>) -> anyhow::Result<Self> {
+        let mut db = open_database()?;
+
+        // Fonts, codecs, runtimes, and addons are only listed when searched for by kind
+        db.set_kinds(Some(ComponentKind::APPS.to_vec()));
 
         let list = gtk::ListBox::new();
         list.show();
@@ -38,8 +52,41 @@ impl App {
 
         scroller.add(&list);
 
+        // Pages are otherwise only fetched on reaching the bottom edge, which never happens if
+        // the results do not fill the window, so keep fetching until they do
+        if let Some(adjustment) = scroller.get_vadjustment() {
+            adjustment.connect_changed(glib::clone!(@strong tx => move |adjustment| {
+                if adjustment.get_upper() <= adjustment.get_page_size() {
+                    let tx = tx.clone();
+                    utils::block_on(async move {
+                        let _ = tx.send(Event::SearchMore).await;
+                    });
+                }
+            }));
+        }
+
+        scroller.connect_edge_reached(glib::clone!(@strong tx => move |_, position| {
+            if position == gtk::PositionType::Bottom {
+                let tx = tx.clone();
+                utils::block_on(async move {
+                    let _ = tx.send(Event::SearchMore).await;
+                });
+            }
+        }));
+
+        let completions = gtk::ListStore::new(&[glib::Type::String]);
+
+        let completion = cascade! {
+            gtk::EntryCompletion::new();
+            ..set_model(Some(&completions));
+            ..set_text_column(0);
+            // The database has already matched each completion against the input
+            ..set_match_func(|_, _, _| true);
+        };
+
         let search = cascade! {
             gtk::SearchEntry::new();
+            ..set_completion(Some(&completion));
             ..connect_changed(glib::clone!(@strong tx => move |_| {
                 let tx = tx.clone();
                 utils::block_on(async move {
@@ -49,9 +96,23 @@ impl App {
             ..show();
         };
 
+        let correction = cascade! {
+            gtk::Label::new(None);
+            ..set_xalign(0.0);
+            ..set_no_show_all(true);
+            ..connect_activate_link(glib::clone!(@strong tx => move |_, _| {
+                let tx = tx.clone();
+                utils::block_on(async move {
+                    let _ = tx.send(Event::SearchVerbatim).await;
+                });
+                gtk::Inhibit(true)
+            }));
+        };
+
         let container = cascade! {
             gtk::Box::new(gtk::Orientation::Vertical, 8);
             ..add(&search);
+            ..add(&correction);
             ..add(&scroller);
         };
 
@@ -65,7 +126,7 @@ impl App {
             ..show_all();
         };
 
-        Self { db, search, list, tx, app_list: HashMap::new() }
+        Ok(Self { db, search, completions, correction, results: None, list, tx })
     }
 
     pub async fn refresh_database(&mut self) {
@@ -73,50 +134,112 @@ impl App {
     }
 
     pub async fn search(&mut self) {
+        self.search_with(true).await;
+    }
+
+    /// Searches for the query as it was typed, after it had been corrected.
+    pub async fn search_verbatim(&mut self) {
+        self.search_with(false).await;
+    }
+
+    async fn search_with(&mut self, correct: bool) {
         for child in self.list.get_children() {
             self.list.remove(&child);
         }
 
-        self.app_list.clear();
+        self.completions.clear();
+        self.correction.hide();
+        self.results = None;
 
         let text = self.search.get_text();
 
         if text.len() < 2 { return; }
 
-        let mut entries = self.db.search_for(&text).await.into_iter();
+        if correct {
+            for completion in self.db.complete(&text, 8) {
+                self.completions.insert_with_values(None, &[0], &[&completion]);
+            }
+        }
 
-        while let Some((origin_name, entity, name)) = entries.next() {
-            let origin = self.db.get_origin(&origin_name);
+        let results = if correct {
+            self.db.search(&text).await
+        } else {
+            self.db.search_verbatim(&text).await
+        };
 
-            let id = origin.id(entity);
-            let icon = origin.icon(entity);
-            let summary = origin.summary(entity);
+        if let Some(suggestion) = results.suggestion() {
+            self.correction.set_markup(&format!(
+                "Showing results for <b>{}</b>. <a href=\"#\">Search instead for {}</a>",
+                glib::markup_escape_text(suggestion),
+                glib::markup_escape_text(&text)
+            ));
 
-            // TODO: Improve the ergonomics of this
-            let data = id.and_then(|n| icon.map(|i| (n, i)))
-                .and_then(|v| summary.map(|s| (v, s)));
+            self.correction.show();
+        }
 
-            if let Some(((id, icon), summary)) = data {
-                let mut add_app = false;
-                self.app_list.entry(id)
-                    .or_insert_with(|| {
-                        add_app = true;
-                        AppMeta { discovered: HashMap::default() }
-                    })
-                    .discovered
-                    .insert(origin_name, entity);
+        self.results = Some(results);
+        self.search_more();
+    }
 
-                if add_app {
-                    self.add_app(&name, &icon, &summary);
+    /// Displays the next page of results from the last search.
+    pub fn search_more(&mut self) {
+        // A page that adds nothing to the list does not resize it, and so would not lead to the
+        // page after it being fetched
+        loop {
+            let apps = match self.results.as_mut() {
+                Some(results) if !results.is_exhausted() => results.next_page(&self.db, PAGE_SIZE),
+                _ => return
+            };
+
+            let mut added = false;
+
+            for app in apps {
+                let (origin_name, entity) = app.preferred();
+                let origin = match self.db.origins.get(&**origin_name) {
+                    Some(origin) => origin,
+                    None => continue
+                };
+
+                let icon = origin.icon(entity);
+                let summary = origin.summary(entity, &self.db.locale);
+                let desktop_file = self.db.desktop_file(origin_name, entity);
+
+                if let Some((icon, summary)) = icon.and_then(|i| summary.map(|s| (i, s))) {
+                    added |= self.add_app(&app.name, &icon, &summary, desktop_file);
                 }
             }
+
+            if added {
+                return;
+            }
         }
     }
 
-    pub fn add_app(&mut self, name: &str, icon: &str, summary: &str) {
+    /// Adds an app to the list of results, returning `false` if its icon is missing.
+    pub fn add_app(&mut self, name: &str, icon: &str, summary: &str, desktop_file: Option<PathBuf>) -> bool {
         if let Ok(Some(img)) = self.db.icons.open_tree("48x48").unwrap().get(icon.as_bytes()) {
-            let listing = AppListing::new(name, summary, &img);
+            let listing = AppListing::new(name, summary, &img, desktop_file);
             self.list.add(&listing.container);
+            return true;
+        }
+
+        false
+    }
+}
+
+/// Opens the cache, waiting out a `command-not-found` process which holds its lock while it
+/// looks up a command.
+fn open_database() -> anyhow::Result<Database> {
+    let mut attempts = 1;
+
+    loop {
+        match Database::open(crate::db_path(), Locale::from_env()) {
+            Ok(db) => return Ok(db),
+            Err(_) if attempts < OPEN_ATTEMPTS => {
+                attempts += 1;
+                std::thread::sleep(Duration::from_millis(50));
+            }
+            Err(why) => return Err(why)
         }
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,48 @@
+use appstream_cache::locale::Locale;
+use appstream_cache::Database;
+
+/// Exit status of a shell when a command is not found.
+const NOT_FOUND: i32 = 127;
+
+/// Prints the packages which provide a command, in the manner of `command-not-found`.
+///
+/// Only the cache left by the GUI is read, since the shell waits on this. If it has not been
+/// created, or the GUI holds its lock, no packages are suggested.
+pub fn command_not_found(command: &str) -> i32 {
+    let candidates = open_cache()
+        .map(|db| db.command_not_found(command))
+        .unwrap_or_default();
+
+    if candidates.is_empty() {
+        eprintln!("{}: command not found", command);
+    } else if candidates.iter().all(|candidate| candidate.command == command) {
+        eprintln!("Command '{}' not found, but can be installed with:\n", command);
+        for candidate in candidates {
+            eprintln!("{}  # {} ({})", candidate.install_hint(), candidate.name, candidate.origin);
+        }
+    } else {
+        eprintln!("Command '{}' not found, did you mean:\n", command);
+        for candidate in candidates {
+            eprintln!(
+                "  command '{}' from {} ({}), installed with: {}",
+                candidate.command,
+                candidate.name,
+                candidate.origin,
+                candidate.install_hint()
+            );
+        }
+    }
+
+    NOT_FOUND
+}
+
+fn open_cache() -> Option<Database> {
+    let path = crate::db_path();
+    if !path.is_dir() {
+        return None;
+    }
+
+    let mut db = Database::open(path, Locale::from_env()).ok()?;
+    db.open_cached().ok()?;
+    Some(db)
+}
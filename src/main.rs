@@ -2,27 +2,57 @@
 extern crate cascade;
 
 mod app;
+mod cli;
 mod utils;
 mod widgets;
 
 use gio::prelude::*;
 use self::app::App;
+use std::env;
+use std::path::{Path, PathBuf};
 
 const APP_ID: &str = "io.github.mmstick.AppsRUs";
 
+// Where the appstream cache is stored, shared by the GUI and the command line
+fn db_path() -> PathBuf {
+    env::var_os("XDG_CACHE_HOME")
+        .map(PathBuf::from)
+        .filter(|path| path.is_absolute())
+        .or_else(|| env::var_os("HOME").map(|home| Path::new(&home).join(".cache")))
+        .unwrap_or_else(env::temp_dir)
+        .join("appinst")
+}
+
 #[derive(Debug)]
 pub enum Event {
     Search,
+    SearchMore,
+    SearchVerbatim,
 }
 
 fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    // Shell hooks may pass the arguments of the command along with it
+    if let [mode, command, ..] = args.as_slice() {
+        if mode == "command-not-found" {
+            std::process::exit(cli::command_not_found(command));
+        }
+    }
+
     let app = gtk::Application::new(Some(APP_ID), Default::default())
         .expect("failed to init application");
 
     app.connect_activate(|app| {
         let (tx, rx) = smol::channel::unbounded();
 
-        let mut app = App::new(app, tx);
+        let mut app = match App::new(app, tx) {
+            Ok(app) => app,
+            Err(why) => {
+                eprintln!("failed to open the appstream cache: {}", why);
+                app.quit();
+                return;
+            }
+        };
 
         let event_handler = async move {
             app.refresh_database().await;
@@ -31,7 +61,9 @@ fn main() {
                 let start = std::time::SystemTime::now();
 
                 match event {
-                    Event::Search => app.search().await
+                    Event::Search => app.search().await,
+                    Event::SearchMore => app.search_more(),
+                    Event::SearchVerbatim => app.search_verbatim().await,
                 }
 
                 let elapsed = std::time::SystemTime::now();
@@ -1,4 +1,6 @@
+use gio::prelude::*;
 use gtk::prelude::*;
+use std::path::PathBuf;
 use super::embedded_png;
 
 pub struct AppListing {
@@ -6,7 +8,8 @@ pub struct AppListing {
 }
 
 impl AppListing {
-    pub fn new(name: &str, summary: &str, icon: &[u8]) -> Self {
+    /// Shows an "Open" button if the app is installed, launching it through its `.desktop` file.
+    pub fn new(name: &str, summary: &str, icon: &[u8], desktop_file: Option<PathBuf>) -> Self {
         let name = gtk::LabelBuilder::new()
             .xalign(0.0)
             .label(name)
@@ -26,9 +29,28 @@ impl AppListing {
             };
             ..attach(&name, 1, 0, 1, 1);
             ..attach(&summary, 1, 1, 1, 1);
+            if let Some(desktop_file) = desktop_file {
+                grid.attach(&open_button(desktop_file), 2, 0, 1, 2);
+            };
             ..show_all();
         };
 
         Self { container }
     }
-}
\ No newline at end of file
+}
+
+fn open_button(desktop_file: PathBuf) -> gtk::Button {
+    cascade! {
+        gtk::Button::with_label("Open");
+        ..set_hexpand(true);
+        ..set_halign(gtk::Align::End);
+        ..set_valign(gtk::Align::Center);
+        ..connect_clicked(move |_| {
+            if let Some(app) = gio::DesktopAppInfo::from_filename(&desktop_file) {
+                if let Err(why) = app.launch(&[], None::<&gio::AppLaunchContext>) {
+                    eprintln!("failed to launch {}: {}", desktop_file.display(), why);
+                }
+            }
+        });
+    }
+}